@@ -1,393 +1,8536 @@
-use calamine::{open_workbook, DataType, Reader, Xlsx};
-use chrono::{Duration as ChronoDuration, Local, NaiveDate};
-use indexmap::IndexMap;
+use calamine::{DataType, Ods, Range, Reader, Sheets, Xls, Xlsb, Xlsx};
+use chrono::{Duration as ChronoDuration, Local, NaiveDate, NaiveDateTime, SecondsFormat};
+use encoding_rs::{Encoding, UTF_8};
+use flate2::write::GzEncoder;
+use indexmap::{IndexMap, IndexSet};
 use json::JsonValue;
-use serde::Serialize;
+use rayon::prelude::*;
+use regex::Regex;
+use serde::ser::SerializeMap;
+use serde::{Deserialize, Serialize, Serializer};
 use serde_json::json;
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::fs::{self, File};
-use std::io::BufReader;
+use std::io::{BufReader, Cursor, Read, Seek, SeekFrom, Write};
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Emitter};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 use walkdir::WalkDir;
 use zip::write::FileOptions;
 use zip::CompressionMethod;
 
 /// 日志类型
-#[derive(Serialize)]
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
 #[serde(rename_all = "lowercase")]
-enum LogType {
+pub enum LogType {
     Info,
     Success,
     Warning,
     Error,
 }
 
-/// 封装事件发送方法，同时包含消息类型
-fn send_progress(app: &AppHandle, msg: &str, log_type: LogType) -> Result<(), String> {
-    let payload = json!({
-        "message": msg,
-        "type": log_type
-    });
-    app.emit("excel-export-progress", payload)
-        .map_err(|e| format!("发送进度事件失败: {}", e))
+/// 转换过程中发出的事件
+pub enum ProgressEvent<'e> {
+    /// 日志消息，progress 为 0.0 ~ 1.0 的整体进度
+    Log {
+        message: &'e str,
+        log_type: LogType,
+        progress: Option<f32>,
+    },
+    /// 转换结束 (校验模式下有错误时也会发出)
+    Complete(&'e ConvertReport),
 }
 
-/// 检查字符串里的 {{}} 是否完整
-fn check_placeholders(value: &str) -> Result<(), String> {
-    let mut stack = 0;
-    for ch in value.chars() {
-        match ch {
-            '{' => stack += 1,
-            '}' => {
-                if stack == 0 {
-                    return Err(format!("占位符不完整: {}", value));
-                }
-                stack -= 1;
-            }
-            _ => {}
+type ProgressCallback<'a> = dyn Fn(ProgressEvent) -> Result<(), String> + Send + Sync + 'a;
+
+/// 转换核心的进度出口和取消标记，
+/// Tauri 命令把事件转发给前端，命令行或测试可以打印、收集或直接丢弃
+pub struct Progress<'a> {
+    callback: Box<ProgressCallback<'a>>,
+    cancelled: Option<&'a AtomicBool>,
+}
+
+impl<'a> Progress<'a> {
+    pub fn new(callback: impl Fn(ProgressEvent) -> Result<(), String> + Send + Sync + 'a) -> Self {
+        Self {
+            callback: Box::new(callback),
+            cancelled: None,
         }
     }
-    if stack != 0 {
-        return Err(format!("占位符不完整: {}", value));
+
+    /// 丢弃所有事件
+    pub fn silent() -> Self {
+        Self::new(|_| Ok(()))
+    }
+
+    /// 转换过程中会检查该标记，设置为 true 后尽快中止
+    pub fn with_cancel_flag(mut self, cancelled: &'a AtomicBool) -> Self {
+        self.cancelled = Some(cancelled);
+        self
+    }
+
+    /// 丢弃事件但保留取消标记
+    fn muted(&self) -> Progress<'a> {
+        Progress {
+            callback: Box::new(|_| Ok(())),
+            cancelled: self.cancelled,
+        }
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancelled.is_some_and(|c| c.load(Ordering::SeqCst))
+    }
+
+    fn emit(&self, event: ProgressEvent) -> Result<(), String> {
+        (self.callback)(event)
     }
-    Ok(())
 }
 
-// 配置结构体
-#[derive(Debug, Clone)]
-struct SheetConfig {
-    name: String,
-    sheet_type: Option<String>,
+/// 封装事件发送方法，同时包含消息类型
+fn send_progress(progress: &Progress, msg: &str, log_type: LogType) -> Result<(), String> {
+    emit_progress(progress, msg, log_type, None)
 }
 
-#[derive(Debug, Clone)]
-struct LanguageConfig {
-    code: String,
+/// 同 send_progress，额外携带 0.0 ~ 1.0 的整体进度
+fn send_progress_pct(
+    progress: &Progress,
+    msg: &str,
+    log_type: LogType,
+    pct: f32,
+) -> Result<(), String> {
+    emit_progress(progress, msg, log_type, Some(pct))
 }
 
-// 从 Excel 读取语言配置
-fn read_language_configs_from_excel(
-    workbook: &mut Xlsx<BufReader<fs::File>>,
-) -> Result<Vec<LanguageConfig>, Box<dyn std::error::Error>> {
-    let sheet_name = "导出语言管理";
-    let range = workbook
-        .worksheet_range(sheet_name)
-        .ok_or_else(|| format!("未找到工作表: {}", sheet_name))??;
+fn emit_progress(
+    progress: &Progress,
+    msg: &str,
+    log_type: LogType,
+    pct: Option<f32>,
+) -> Result<(), String> {
+    progress.emit(ProgressEvent::Log {
+        message: msg,
+        log_type,
+        progress: pct.map(|p| p.clamp(0.0, 1.0)),
+    })
+}
 
-    let mut configs = Vec::new();
+fn send_complete(progress: &Progress, report: &ConvertReport) -> Result<(), String> {
+    progress.emit(ProgressEvent::Complete(report))
+}
 
-    for row in range.rows() {
-        if !row.is_empty() {
-            let lang_code = get_cell_string(&row[0]);
-            if !lang_code.is_empty() {
-                configs.push(LanguageConfig { code: lang_code });
+/// 将转换事件转发给前端的 excel-export-progress / excel-export-complete 事件
+fn app_progress(app: &AppHandle) -> Progress<'_> {
+    Progress::new(move |event| match event {
+        ProgressEvent::Log {
+            message,
+            log_type,
+            progress,
+        } => {
+            let mut payload = json!({
+                "message": message,
+                "type": log_type
+            });
+            // 只有提供进度时才带上 progress 字段
+            if let Some(pct) = progress {
+                payload["progress"] = json!(pct);
             }
+            app.emit("excel-export-progress", payload)
+                .map_err(|e| format!("发送进度事件失败: {}", e))
         }
-    }
-
-    Ok(configs)
+        ProgressEvent::Complete(report) => app
+            .emit("excel-export-complete", report.clone())
+            .map_err(|e| format!("发送完成事件失败: {}", e)),
+    })
 }
 
-fn read_sheet_configs_from_excel(
-    app: &AppHandle,
-    workbook: &mut Xlsx<BufReader<fs::File>>,
-) -> Result<Vec<SheetConfig>, Box<dyn std::error::Error>> {
-    let sheet_name = "导出sheet管理";
-    let range = workbook
-        .worksheet_range(sheet_name)
-        .ok_or_else(|| format!("未找到工作表: {}", sheet_name))??;
-
-    let mut configs = Vec::new();
+/// 命令返回给前端的错误。
+/// 序列化为 `{ kind, message, ...定位字段 }`，前端可以按 kind 和定位字段跳转到出错的单元格，
+/// message 为与 Display 相同的可读信息
+#[derive(Debug)]
+pub enum ExportError {
+    FileNotFound {
+        path: String,
+    },
+    SheetNotFound {
+        sheet: String,
+        /// 工作簿中现有的工作表
+        available: Vec<String>,
+    },
+    PlaceholderError {
+        sheet: String,
+        row: usize,
+        key: String,
+        value: String,
+        reason: String,
+    },
+    NestedKeyConflict {
+        sheet: String,
+        key: String,
+        reason: String,
+    },
+    ValueTooLong {
+        sheet: String,
+        row: usize,
+        language: String,
+        key: String,
+        length: usize,
+        max: usize,
+        /// formula_diagnostics 开启且单元格为公式时的公式文本
+        formula: Option<String>,
+    },
+    GlossaryError {
+        sheet: String,
+        row: usize,
+        language: String,
+        key: String,
+        reason: String,
+    },
+    KeyNaming {
+        sheet: String,
+        row: usize,
+        key: String,
+        convention: String,
+    },
+    /// 单元格是 Excel 错误值 (#REF! / #DIV/0! 等)
+    CellError {
+        sheet: String,
+        row: usize,
+        language: String,
+        key: String,
+        error: String,
+    },
+    /// fail_on_empty 开启时收集到的全部空值
+    EmptyValues {
+        issues: Vec<Issue>,
+    },
+    /// 各语言缺失或为空的必需 key
+    MissingRequiredKeys {
+        issues: Vec<Issue>,
+    },
+    /// strict 开启时工作表之间全部的 key 冲突
+    KeyCollisions {
+        issues: Vec<Issue>,
+    },
+    /// 加密工作簿无法打开
+    Encryption {
+        path: String,
+        problem: EncryptionProblem,
+        detail: Option<String>,
+    },
+    Cancelled,
+    Io(String),
+    Other(String),
+}
 
-    for row in range.rows() {
-        if row.len() < 1 {
-            continue;
+impl ExportError {
+    fn kind(&self) -> &'static str {
+        match self {
+            ExportError::FileNotFound { .. } => "file_not_found",
+            ExportError::SheetNotFound { .. } => "sheet_not_found",
+            ExportError::PlaceholderError { .. } => "placeholder_error",
+            ExportError::NestedKeyConflict { .. } => "nested_key_conflict",
+            ExportError::ValueTooLong { .. } => "value_too_long",
+            ExportError::GlossaryError { .. } => "glossary_error",
+            ExportError::KeyNaming { .. } => "key_naming",
+            ExportError::CellError { .. } => "cell_error",
+            ExportError::EmptyValues { .. } => "empty_values",
+            ExportError::MissingRequiredKeys { .. } => "missing_required_keys",
+            ExportError::KeyCollisions { .. } => "key_collisions",
+            ExportError::Encryption { .. } => "encryption",
+            ExportError::Cancelled => "cancelled",
+            ExportError::Io(_) => "io_error",
+            ExportError::Other(_) => "other",
         }
+    }
+}
 
-        let name = get_cell_string(&row[0]);
-        let sheet_type = if row.len() > 1 {
-            let s = get_cell_string(&row[1]);
-            if s.trim().is_empty() {
-                None
-            } else {
-                Some(s)
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::FileNotFound { path } => write!(f, "文件不存在: {}", path),
+            ExportError::SheetNotFound { sheet, available } => write!(
+                f,
+                "未找到工作表: {} (现有工作表: {})",
+                sheet,
+                available.join(", ")
+            ),
+            ExportError::PlaceholderError {
+                sheet,
+                row,
+                key,
+                value,
+                reason,
+            } => write!(
+                f,
+                "占位符校验失败 Sheet: '{}' 行: {} Key: '{}' 值: '{}' 错误: {}",
+                sheet, row, key, value, reason
+            ),
+            ExportError::NestedKeyConflict { sheet, key, reason } => write!(
+                f,
+                "嵌套 key 校验失败 Sheet: '{}' Key: '{}' 错误: {}",
+                sheet, key, reason
+            ),
+            ExportError::ValueTooLong {
+                sheet,
+                row,
+                language,
+                key,
+                length,
+                max,
+                formula,
+            } => write!(
+                f,
+                "值过长 Sheet: '{}' 行: {} 列: '{}' Key: '{}' 长度: {} (上限 {}){}",
+                sheet,
+                row,
+                language,
+                key,
+                length,
+                max,
+                formula_note(formula.as_deref())
+            ),
+            ExportError::GlossaryError {
+                sheet,
+                row,
+                language,
+                key,
+                reason,
+            } => write!(
+                f,
+                "术语替换失败 Sheet: '{}' 行: {} 列: '{}' Key: '{}' 错误: {}",
+                sheet, row, language, key, reason
+            ),
+            ExportError::KeyNaming {
+                sheet,
+                row,
+                key,
+                convention,
+            } => write!(
+                f,
+                "Key 命名不符合 {} 规范 Sheet: '{}' 行: {} Key: '{}'",
+                convention, sheet, row, key
+            ),
+            ExportError::CellError {
+                sheet,
+                row,
+                language,
+                key,
+                error,
+            } => write!(
+                f,
+                "单元格错误 Sheet: '{}' 行: {} 列: '{}' Key: '{}' 错误: {}",
+                sheet, row, language, key, error
+            ),
+            ExportError::EmptyValues { issues } => {
+                write!(f, "存在 {} 个空值:", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n{}", issue.message)?;
+                }
+                Ok(())
             }
-        } else {
-            None
-        };
-
-        configs.push(SheetConfig { name, sheet_type });
+            ExportError::MissingRequiredKeys { issues } => {
+                write!(f, "缺少 {} 个必需 key:", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n{}", issue.message)?;
+                }
+                Ok(())
+            }
+            ExportError::KeyCollisions { issues } => {
+                write!(f, "存在 {} 个 key 冲突:", issues.len())?;
+                for issue in issues {
+                    write!(f, "\n{}", issue.message)?;
+                }
+                Ok(())
+            }
+            ExportError::Encryption {
+                path,
+                problem,
+                detail,
+            } => {
+                match problem {
+                    EncryptionProblem::PasswordRequired => {
+                        write!(f, "工作簿已加密, 需要提供密码: {}", path)?
+                    }
+                    EncryptionProblem::WrongPassword => {
+                        write!(f, "密码错误, 无法打开工作簿: {}", path)?
+                    }
+                    EncryptionProblem::NotEncrypted => {
+                        write!(f, "工作簿没有加密, 不需要密码: {}", path)?
+                    }
+                    EncryptionProblem::Unsupported => {
+                        write!(f, "不支持该工作簿的加密方式: {}", path)?
+                    }
+                }
+                match detail {
+                    Some(detail) => write!(f, " ({})", detail),
+                    None => Ok(()),
+                }
+            }
+            ExportError::Cancelled => write!(f, "导出已取消"),
+            ExportError::Io(msg) | ExportError::Other(msg) => write!(f, "{}", msg),
+        }
     }
-
-    Ok(configs)
 }
 
-fn get_cell_string(cell: &DataType) -> String {
-    match cell {
-        DataType::String(s) => s.to_string(),
-        DataType::Float(f) => {
-            if *f > 30000.0 && *f < 70000.0 {
-                let base_date = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-                let date = base_date + ChronoDuration::days(*f as i64);
-                date.format("%Y-%m-%d").to_string()
-            } else if f.fract() == 0.0 {
-                format!("{:.0}", f)
-            } else {
-                f.to_string()
+impl std::error::Error for ExportError {}
+
+impl Serialize for ExportError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(None)?;
+        map.serialize_entry("kind", self.kind())?;
+        map.serialize_entry("message", &self.to_string())?;
+        match self {
+            ExportError::FileNotFound { path } => map.serialize_entry("path", path)?,
+            ExportError::SheetNotFound { sheet, available } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("available", available)?;
             }
+            ExportError::PlaceholderError {
+                sheet,
+                row,
+                key,
+                value,
+                ..
+            } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("row", row)?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("value", value)?;
+            }
+            ExportError::NestedKeyConflict { sheet, key, .. } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("key", key)?;
+            }
+            ExportError::ValueTooLong {
+                sheet,
+                row,
+                language,
+                key,
+                length,
+                max,
+                formula,
+            } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("row", row)?;
+                map.serialize_entry("language", language)?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("length", length)?;
+                map.serialize_entry("max", max)?;
+                if let Some(formula) = formula {
+                    map.serialize_entry("formula", formula)?;
+                }
+            }
+            ExportError::GlossaryError {
+                sheet,
+                row,
+                language,
+                key,
+                ..
+            } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("row", row)?;
+                map.serialize_entry("language", language)?;
+                map.serialize_entry("key", key)?;
+            }
+            ExportError::KeyNaming {
+                sheet,
+                row,
+                key,
+                convention,
+            } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("row", row)?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("convention", convention)?;
+            }
+            ExportError::CellError {
+                sheet,
+                row,
+                language,
+                key,
+                error,
+            } => {
+                map.serialize_entry("sheet", sheet)?;
+                map.serialize_entry("row", row)?;
+                map.serialize_entry("language", language)?;
+                map.serialize_entry("key", key)?;
+                map.serialize_entry("error", error)?;
+            }
+            ExportError::EmptyValues { issues }
+            | ExportError::MissingRequiredKeys { issues }
+            | ExportError::KeyCollisions { issues } => map.serialize_entry("issues", issues)?,
+            ExportError::Encryption { path, problem, .. } => {
+                map.serialize_entry("path", path)?;
+                map.serialize_entry("problem", problem)?;
+            }
+            ExportError::Cancelled | ExportError::Io(_) | ExportError::Other(_) => {}
         }
-        DataType::Int(i) => i.to_string(),
-        DataType::Bool(b) => b.to_string(),
-        DataType::DateTime(dt) => {
-            let base_date = NaiveDate::from_ymd_opt(1899, 12, 30).unwrap();
-            let days = dt.trunc() as i64;
-            let seconds = ((*dt - dt.trunc()) * 86400.0) as i64;
-            let datetime = base_date.and_hms_opt(0, 0, 0).unwrap()
-                + ChronoDuration::days(days)
-                + ChronoDuration::seconds(seconds);
-            datetime.format("%Y-%m-%d %H:%M:%S").to_string()
-        }
-        DataType::Duration(d) => {
-            let total_seconds = (*d * 86400.0) as i64;
-            let hours = total_seconds / 3600;
-            let minutes = (total_seconds % 3600) / 60;
-            let seconds = total_seconds % 60;
-            format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
-        }
-        DataType::DateTimeIso(s) => s.clone(),
-        DataType::DurationIso(s) => s.clone(),
-        DataType::Error(e) => format!("Error: {:?}", e),
-        DataType::Empty => String::new(),
+        map.end()
     }
 }
 
-/// 压缩整个文件夹为 zip 文件
-fn zip_directory(src_dir: &Path, dst_file: &Path) -> Result<(), String> {
-    let file = File::create(dst_file).map_err(|e| format!("创建 zip 文件失败: {}", e))?;
-    let mut zip = zip::ZipWriter::new(file);
-    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+/// 值是否按空值处理，`treat_blank_as_empty` 时只有空白字符的值也算空值
+fn is_blank(value: &str, treat_blank_as_empty: bool) -> bool {
+    value.is_empty() || (treat_blank_as_empty && value.trim().is_empty())
+}
 
-    let base_path = src_dir.parent().unwrap_or_else(|| Path::new(""));
+/// 错误信息末尾的公式说明，帮助译者理解为什么值看起来是计算出来的
+fn formula_note(formula: Option<&str>) -> String {
+    formula
+        .map(|f| format!(" (单元格为公式: ={})", f))
+        .unwrap_or_default()
+}
 
-    for entry in WalkDir::new(src_dir) {
-        let entry = entry.map_err(|e| format!("读取目录失败: {}", e))?;
-        let path = entry.path();
+impl From<String> for ExportError {
+    fn from(msg: String) -> Self {
+        ExportError::Other(msg)
+    }
+}
 
-        if path.is_file() {
-            // 计算相对路径（去掉上级目录）
-            let name = path.strip_prefix(base_path).unwrap();
-            let name_str = name.to_string_lossy();
+impl From<std::io::Error> for ExportError {
+    fn from(e: std::io::Error) -> Self {
+        ExportError::Io(e.to_string())
+    }
+}
 
-            let mut f = File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
-            zip.start_file(name_str, options)
-                .map_err(|e| format!("写入 zip 条目失败: {}", e))?;
+/// 校验问题级别
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueLevel {
+    #[default]
+    Warning,
+    Error,
+}
+
+/// 校验问题类别
+#[derive(Serialize, Clone, Copy, Default, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueCategory {
+    #[default]
+    SheetError,
+    EmptyValue,
+    DuplicateKey,
+    PlaceholderError,
+    PlaceholderMismatch,
+    MissingKey,
+    NestedKeyConflict,
+    MissingPlural,
+    KeyWhitespace,
+    ValueTooLong,
+    GlossaryError,
+    KeyNaming,
+    MissingRequiredKey,
+    CellError,
+    UnknownColumn,
+    InvisibleChar,
+    DuplicateValue,
+    KeyCollision,
+    EmptyLanguage,
+    DateOutOfRange,
+}
+
+/// 单条校验问题，定位信息不适用时留空
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct Issue {
+    pub level: IssueLevel,
+    pub category: IssueCategory,
+    pub sheet: Option<String>,
+    pub row: Option<usize>,
+    pub language: Option<String>,
+    pub key: Option<String>,
+    pub message: String,
+}
+
+/// 一次导出过程中收集到的所有问题，最终写入 report.json
+#[derive(Default)]
+struct ExportReport {
+    issues: Vec<Issue>,
+}
 
-            std::io::copy(&mut f, &mut zip).map_err(|e| format!("写入 zip 内容失败: {}", e))?;
+impl ExportReport {
+    /// 发送对应级别的进度事件并记录问题
+    fn record(&mut self, progress: &Progress, issue: Issue) -> Result<(), String> {
+        let log_type = match issue.level {
+            IssueLevel::Warning => LogType::Warning,
+            IssueLevel::Error => LogType::Error,
+        };
+        send_progress(progress, &issue.message, log_type)?;
+        self.issues.push(issue);
+        Ok(())
+    }
+
+    /// 只写入报告，不发送事件。逐行的高频问题由调用方按工作表汇总后提示
+    fn record_silent(&mut self, issue: Issue) {
+        self.issues.push(issue);
+    }
+
+    fn count(&self, level: IssueLevel) -> usize {
+        self.issues.iter().filter(|i| i.level == level).count()
+    }
+
+    fn to_json(&self) -> Result<String, String> {
+        #[derive(Serialize)]
+        struct ReportFile<'a> {
+            errors: usize,
+            warnings: usize,
+            issues: &'a [Issue],
         }
+        let report = ReportFile {
+            errors: self.count(IssueLevel::Error),
+            warnings: self.count(IssueLevel::Warning),
+            issues: &self.issues,
+        };
+        serde_json::to_string_pretty(&report).map_err(|e| format!("生成报告失败: {}", e))
     }
+}
 
-    zip.finish().map_err(|e| format!("关闭 zip 失败: {}", e))?;
-    Ok(())
+/// 一次转换的结果，同时作为导出完成事件 (excel-export-complete) 的数据，供前端直接展示结果
+#[derive(Serialize, Clone, Default, Debug)]
+pub struct ConvertReport {
+    /// 生成的压缩包 (zip / tar.gz) 路径，未压缩时为空
+    pub zip_path: Option<String>,
+    /// 保留的导出文件夹路径，已压缩并删除时为空
+    pub output_dir: Option<String>,
+    pub language_count: usize,
+    /// 导出内容中的文件，相对于导出文件夹 (即 zip 内的路径)
+    pub file_paths: Vec<String>,
+    pub warning_count: usize,
+    pub error_count: usize,
+    /// 各语言的 key 数量和完成度
+    pub coverage: Vec<LanguageCoverage>,
+    /// 可读的结果描述，即命令的返回值
+    #[serde(skip)]
+    pub message: String,
+    /// 收集到的全部问题，与 report.json 内容一致
+    #[serde(skip)]
+    pub issues: Vec<Issue>,
 }
 
-#[tauri::command]
-async fn convert_excel_to_json(app: AppHandle, path: String) -> Result<String, String> {
-    send_progress(&app, &format!("开始处理文件: {}", path), LogType::Info)?;
+/// 单个语言的翻译完成度
+#[derive(Serialize, Clone, Debug)]
+pub struct LanguageCoverage {
+    pub lang: String,
+    pub total_keys: usize,
+    /// 值不为空的 key 数量 (不含回退的值)
+    pub non_empty_keys: usize,
+    /// 使用基准语言回退的 key 数量
+    pub fallback_keys: usize,
+    /// 完成度百分比，保留两位小数，计算方式见 language_coverage
+    pub coverage_pct: f64,
+}
 
-    let file_path = PathBuf::from(&path);
-    if !file_path.exists() {
-        let msg = format!("文件不存在: {}", file_path.display());
-        send_progress(&app, &msg, LogType::Error)?;
-        return Err(msg);
+/// 导出任务共享状态
+#[derive(Default)]
+struct ExportState {
+    /// 取消标记，由 cancel_export 命令设置，每次开始导出时重置
+    cancelled: AtomicBool,
+}
+
+/// 文件监听状态，同一时间只保留一个监听
+#[derive(Default)]
+struct WatchState {
+    /// 释放 watcher 后事件通道关闭，监听线程随之退出
+    watcher: Mutex<Option<notify::RecommendedWatcher>>,
+}
+
+/// 文件在这段时间内没有新的变化才重新导出，避免一次保存触发多次导出
+const WATCH_DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 已取消时清理未完成的导出目录并返回错误，不写文件时 `output_dir` 为 None
+fn abort_if_cancelled(progress: &Progress, output_dir: Option<&Path>) -> Result<(), ExportError> {
+    if !progress.is_cancelled() {
+        return Ok(());
     }
+    if let Some(dir) = output_dir.filter(|d| d.exists()) {
+        let _ = fs::remove_dir_all(dir);
+    }
+    let err = ExportError::Cancelled;
+    send_progress(progress, &format!("⚠️ {}", err), LogType::Warning)?;
+    Err(err)
+}
 
-    send_progress(&app, "正在打开 Excel 文件...", LogType::Info)?;
-    let mut workbook: Xlsx<_> =
-        open_workbook(&file_path).map_err(|e| format!("打开文件失败: {}", e))?;
-    send_progress(&app, "Excel 文件已成功打开", LogType::Success)?;
+/// 导出文件格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OutputFormat {
+    Json,
+    Yaml,
+    /// 每个语言一个 key,value 两列的 CSV
+    Csv,
+    /// 所有语言合并为一个 all.csv: key 列 + 每个语言一列
+    CsvCombined,
+    /// Android 资源文件 res/values-<lang>/strings.xml
+    Android,
+    /// iOS 资源文件 <lang>.lproj/Localizable.strings
+    Ios,
+    /// gettext 翻译文件 <lang>.po
+    Po,
+    Toml,
+    /// Java 资源文件 messages_<lang>.properties
+    Properties,
+    /// Flutter 资源文件 app_<lang>.arb
+    Arb,
+}
 
-    let lang_configs = read_language_configs_from_excel(&mut workbook)
-        .map_err(|e| format!("读取语言配置失败: {}", e))?;
-    let sheet_configs =
-        read_sheet_configs_from_excel(&app, &mut workbook).map_err(|e| e.to_string())?;
+impl OutputFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Json => "json",
+            OutputFormat::Yaml => "yaml",
+            OutputFormat::Csv | OutputFormat::CsvCombined => "csv",
+            OutputFormat::Android => "xml",
+            OutputFormat::Ios => "strings",
+            OutputFormat::Po => "po",
+            OutputFormat::Toml => "toml",
+            OutputFormat::Properties => "properties",
+            OutputFormat::Arb => "arb",
+        }
+    }
 
-    send_progress(
-        &app,
-        &format!(
-            "读取到 {} 个语言, {} 个工作表",
-            lang_configs.len(),
-            sheet_configs.len()
-        ),
-        LogType::Info,
-    )?;
+    /// 是否可以按工作表拆分输出、使用 filename_pattern 命名。
+    /// Android / iOS / Java / Flutter 的资源文件路径由平台约定，每个语言只有一个；合并格式只有一个文件
+    fn supports_custom_path(self) -> bool {
+        !matches!(
+            self,
+            OutputFormat::CsvCombined
+                | OutputFormat::Android
+                | OutputFormat::Ios
+                | OutputFormat::Properties
+                | OutputFormat::Arb
+        )
+    }
 
-    // 创建导出目录
-    let parent = file_path.parent().unwrap_or_else(|| Path::new("."));
-    let stem = file_path
-        .file_stem()
-        .map(|s| s.to_string_lossy())
-        .unwrap_or_else(|| "export".into());
-    let time_str = Local::now().format("%Y%m%d_%H%M%S").to_string();
-    let export_folder_name = format!("{}_{}", stem, time_str);
-    let output_dir = parent.join(&export_folder_name);
-    fs::create_dir_all(&output_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    /// 单个语言的输出文件相对导出文件夹的路径
+    fn file_path(self, code: &str) -> String {
+        match self {
+            OutputFormat::Android => format!("res/{}/strings.xml", android_values_dir(code)),
+            OutputFormat::Ios => format!("{}.lproj/Localizable.strings", code),
+            // ResourceBundle 的 locale 后缀用下划线，如 messages_zh_CN.properties
+            OutputFormat::Properties => format!("messages_{}.properties", code.replace('-', "_")),
+            OutputFormat::Arb => format!("app_{}.arb", code.replace('-', "_")),
+            _ => format!("{}.{}", code, self.extension()),
+        }
+    }
 
-    send_progress(
-        &app,
-        &format!("导出文件夹创建完成: {}", output_dir.display()),
-        LogType::Success,
-    )?;
+    /// 是否为跨语言合并输出的格式，这类格式在所有语言处理完后统一写入
+    fn is_combined(self) -> bool {
+        matches!(self, OutputFormat::CsvCombined)
+    }
+}
 
-    let mut all_jsons = vec![];
+/// 不拆分输出时默认的文件名模式
+const DEFAULT_FILENAME_PATTERN: &str = "{lang}.{ext}";
 
-    for lang_config in &lang_configs {
-        send_progress(
-            &app,
-            &format!("正在处理语言: {}", lang_config.code),
-            LogType::Info,
-        )?;
+/// 校验输出文件名模式，未指定时按是否拆分输出返回默认模式
+fn parse_filename_pattern(
+    pattern: Option<&str>,
+    split_by_sheet: bool,
+    formats: &[OutputFormat],
+) -> Result<String, String> {
+    let pattern = match pattern.map(str::trim) {
+        Some(p) if !p.is_empty() => p,
+        _ if split_by_sheet => return Ok("{lang}/{sheet}.{ext}".to_string()),
+        _ => return Ok(DEFAULT_FILENAME_PATTERN.to_string()),
+    };
+    let invalid = |reason: &str| Err(format!("文件名模式 '{}' 无效: {}", pattern, reason));
 
-        let mut sheet_data_map: IndexMap<String, IndexMap<String, String>> = IndexMap::new();
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        let Some(len) = rest[start..].find('}') else {
+            return invalid("花括号没有闭合");
+        };
+        let name = &rest[start + 1..start + len];
+        if !matches!(name, "lang" | "sheet" | "ext") {
+            return invalid(&format!("不支持的变量 {{{}}}", name));
+        }
+        rest = &rest[start + len + 1..];
+    }
+    if !pattern.contains("{lang}") {
+        return invalid("必须包含 {lang}");
+    }
+    if split_by_sheet != pattern.contains("{sheet}") {
+        return invalid("{sheet} 只能且必须在按工作表拆分输出时使用");
+    }
+    if formats.iter().filter(|f| f.supports_custom_path()).count() > 1 && !pattern.contains("{ext}")
+    {
+        return invalid("同时输出多种格式时必须包含 {ext}");
+    }
+    let path = Path::new(pattern);
+    if pattern.starts_with(['/', '\\'])
+        || path
+            .components()
+            .any(|c| !matches!(c, std::path::Component::Normal(_)))
+        || pattern.split(['/', '\\']).any(|s| s == "..")
+    {
+        return invalid("必须是导出文件夹内的相对路径，不能包含 ..");
+    }
+    Ok(pattern.to_string())
+}
 
-        for sheet_config in &sheet_configs {
-            let range = match workbook.worksheet_range(&sheet_config.name) {
-                Some(Ok(r)) => r,
-                Some(Err(e)) => {
-                    send_progress(
-                        &app,
-                        &format!("⚠️ 读取工作表 {} 失败: {}", sheet_config.name, e),
-                        LogType::Warning,
-                    )?;
-                    continue;
-                }
-                None => {
-                    send_progress(
-                        &app,
-                        &format!("⚠️ 找不到工作表: {}", sheet_config.name),
-                        LogType::Warning,
-                    )?;
-                    continue;
-                }
-            };
+/// 解析 layout 参数，返回该布局对应的文件名模式，flat 时返回 None
+fn parse_output_layout(
+    value: Option<&str>,
+    split_by_sheet: bool,
+) -> Result<Option<&'static str>, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("flat") => Ok(None),
+        Some("i18next") if split_by_sheet => Ok(Some("locales/{lang}/{sheet}.{ext}")),
+        Some("i18next") => Ok(Some("locales/{lang}/translation.{ext}")),
+        Some(other) => Err(format!("不支持的输出目录结构: {}", other)),
+    }
+}
 
-            let header_row = match range.rows().next() {
-                Some(h) => h,
-                None => continue,
-            };
+/// 按文件名模式生成输出路径
+fn expand_filename_pattern(pattern: &str, code: &str, sheet: Option<&str>, ext: &str) -> PathBuf {
+    PathBuf::from(
+        pattern
+            .replace("{lang}", code)
+            .replace("{sheet}", sheet.unwrap_or_default())
+            .replace("{ext}", ext),
+    )
+}
 
-            let lang_col = header_row
-                .iter()
-                .position(|c| get_cell_string(c) == lang_config.code);
-            let lang_col = match lang_col {
-                Some(c) => c,
-                None => continue,
-            };
+/// 解析 output_format 参数，多个格式用逗号分隔，如 "json,csv"；
+/// 支持 "json" / "yaml" / "both" (json + yaml) / "csv" / "csv_combined" / "android" / "ios" / "po" / "toml"
+/// / "properties" / "arb"
+fn parse_output_formats(value: &str) -> Result<Vec<OutputFormat>, String> {
+    let mut formats = Vec::new();
+    for item in value.split(',') {
+        let parsed: &[OutputFormat] = match item.trim().to_lowercase().as_str() {
+            "" | "json" => &[OutputFormat::Json],
+            "yaml" | "yml" => &[OutputFormat::Yaml],
+            "both" => &[OutputFormat::Json, OutputFormat::Yaml],
+            "csv" => &[OutputFormat::Csv],
+            "csv_combined" => &[OutputFormat::CsvCombined],
+            "android" => &[OutputFormat::Android],
+            "ios" => &[OutputFormat::Ios],
+            "po" => &[OutputFormat::Po],
+            "toml" => &[OutputFormat::Toml],
+            "properties" => &[OutputFormat::Properties],
+            "arb" => &[OutputFormat::Arb],
+            other => return Err(format!("不支持的输出格式: {}", other)),
+        };
+        for format in parsed {
+            if !formats.contains(format) {
+                formats.push(*format);
+            }
+        }
+    }
+    Ok(formats)
+}
 
-            let mut temp: IndexMap<String, String> = IndexMap::new();
+/// 导出选项，前端以 camelCase 传入，未传的字段使用默认值
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ConvertOptions {
+    /// 输出格式，多个用逗号分隔，见 parse_output_formats
+    pub output_format: String,
+    /// 只做解析和校验，不写任何文件
+    pub validate_only: bool,
+    /// 导出目录，未指定时使用源文件所在目录
+    pub output_dir: Option<String>,
+    /// 基准语言，其他语言缺少的 key 会被报告
+    pub base_language: Option<String>,
+    /// zip 压缩方式: "deflated" / "stored" / "bzip2" / "zstd"
+    pub compression: String,
+    /// 压缩等级，不填使用各压缩方式的默认等级
+    pub compression_level: Option<i32>,
+    /// 不压缩，保留导出文件夹 (等同于 archive_format 为 "none")
+    pub skip_zip: bool,
+    /// 导出文件夹的归档格式: "zip" (默认) / "tar.gz" / "none"，compression 相关选项只对 zip 有效
+    pub archive_format: Option<String>,
+    /// 增量更新已存在的同名 zip，只重新压缩内容有变化的条目
+    pub incremental: bool,
+    /// 导出文件夹和 zip 的名称带上导出时间 `<名称>_<YYYYMMDD_HHMMSS>`，默认开启
+    pub timestamped: bool,
+    /// 导出文件夹和 zip 的名称，不填时使用 Excel 文件名
+    pub folder_name: Option<String>,
+    /// 同名的导出文件夹或 zip 已存在时先删除；关闭时报错，不会混入旧文件
+    pub overwrite: bool,
+    /// 压缩包的完整输出路径 (如 `dist/locales.zip`)，不填时放在导出文件夹旁边、与文件夹同名
+    pub zip_path: Option<String>,
+    /// 去除值首尾的空白字符；关闭时保留原样 (UI 文案里的首尾空格可能是有意的)
+    pub trim: bool,
+    /// 将值中的 CRLF / CR 换行统一为 LF
+    pub normalize_newlines: bool,
+    /// 布尔和数字单元格在 JSON / YAML 中保留原类型，而不是写成字符串
+    pub preserve_types: bool,
+    /// 只导出这些语言，为空时导出全部语言；回退链上的其他语言只读取不写出
+    pub only_languages: Option<Vec<String>>,
+    /// key 列为空但该行有内容时沿用上一个 key (纵向合并的 key)
+    pub fill_merged_keys: bool,
+    /// 语言配置工作表名，默认 "导出语言管理"
+    pub lang_config_sheet: Option<String>,
+    /// sheet 配置工作表名，默认 "导出sheet管理"
+    pub sheet_config_sheet: Option<String>,
+    /// 外部配置文件 (.json / .toml)，其中的 languages / sheets 代替工作簿中的配置表
+    pub config_file: Option<String>,
+    /// 加密工作簿 (xlsx / xlsm / xlsb) 的打开密码，支持 Excel 的标准加密 (AES-128) 和 agile 加密 (SHA-512)
+    pub password: Option<String>,
+    /// 不读语言配置表，从各工作表表头推断语言列
+    pub auto_languages: bool,
+    /// 全局的 key 列，列字母 (如 "B") 或从 1 开始的列号，默认 A 列，sheet 配置中的设置优先
+    pub key_column: Option<String>,
+    /// 空值不写入输出 (空值警告照常报告)，让运行时回退到其他语言
+    pub omit_empty: bool,
+    /// 只有空白字符的值按空值处理，写出的值仍保持原样
+    pub treat_blank_as_empty: bool,
+    /// 没有任何有值 key 的语言不写入语言文件
+    pub skip_empty_languages: bool,
+    /// 输出结构: "nested" / "flat"，不填时按各工作表的类型决定
+    pub output_nesting: Option<String>,
+    /// 写入前按 key 字母顺序排序 (不区分大小写，逐层递归)，默认保持表格中的顺序
+    pub sort_keys: bool,
+    /// 允许占位符之外出现单个花括号 (或其他分隔符首尾的括号)
+    pub allow_single_braces: bool,
+    /// 占位符的起止分隔符，默认 `["{{", "}}"]`，只适用于 double_brace 风格
+    pub placeholder_delimiters: (String, String),
+    /// 占位符风格: "double_brace" (默认) / "single_brace" / "printf" / "icu"
+    pub placeholder_style: Option<String>,
+    /// 每个工作表单独输出 (默认为 <lang>/<sheet>.<ext>)，而不是合并为一个 <lang>.<ext>
+    pub split_by_sheet: bool,
+    /// 拆分输出时 root 类型工作表合并写入的文件名 (不含扩展名)，默认 "common"
+    pub root_file_name: Option<String>,
+    /// 把每个语言的全部内容放在该点分路径下，如 "v2" 输出 `{ "v2": { ... } }`
+    pub root_wrapper: Option<String>,
+    /// 输出文件相对导出文件夹的路径模式，可用 `{lang}`、`{sheet}`、`{ext}`，默认 "{lang}.{ext}"
+    pub filename_pattern: Option<String>,
+    /// 输出目录结构: "flat" (默认，按 filename_pattern) / "i18next" (`locales/<lang>/translation.json`)
+    pub layout: Option<String>,
+    /// 以 `#one` / `#other` 等结尾的 key 按复数类别归组: "i18next" / "nested"，不填时不处理
+    pub plural_format: Option<String>,
+    /// 在每个导出的语言文件开头写入 UTF-8 BOM (部分 Windows 工具要求)
+    pub write_bom: bool,
+    /// 导出文件的编码，如 "gbk" / "shift_jis"，默认 UTF-8
+    pub encoding: Option<String>,
+    /// JSON 缩进的空格数，0 输出单行的压缩 JSON，默认 2
+    pub indent: u16,
+    /// properties 格式直接写 UTF-8 字符，默认把非 ASCII 字符转义为 `\uXXXX`
+    pub properties_utf8: bool,
+    /// 去除 key 首尾的空白字符，关闭时只报告警告 (`"ok "` 和 `"ok"` 会被当作两个 key)
+    pub trim_keys: bool,
+    /// 去除 key 和值中的控制字符、零宽字符和 BOM，关闭时只报告警告
+    pub clean_invisible: bool,
+    /// 值的最大长度 (按字符数计算，不是字节数)，超出时报告警告，不填时不检查
+    pub max_value_length: Option<usize>,
+    /// 同一语言中多个 key 的值相同且长度不小于该值时报告警告，不填时不检查
+    pub duplicate_value_min_length: Option<usize>,
+    /// 重复值检查忽略的值，如品牌名、版权声明等本来就会在多处出现的文本
+    pub duplicate_value_allowlist: Vec<String>,
+    /// 值过长、key 命名不规范、单元格错误值和 key 冲突视为错误并中止导出
+    pub strict: bool,
+    /// rtl 语言插入方向控制符: "marks" / "isolate" / "wrap"，不填时值保持原样
+    pub rtl_marks: Option<String>,
+    /// 额外写入 meta.json: 语言列表、工作表配置、导出时间和源文件名
+    pub emit_meta: bool,
+    /// 额外写入 schema.json: 描述合并后语言文件结构的 JSON Schema
+    pub emit_schema: bool,
+    /// schema 中为带占位符的值加上 `pattern`，要求保留所有语言共有的占位符
+    pub schema_patterns: bool,
+    /// 额外写出 template.json: base_language 的全部 key，值都为空字符串
+    pub emit_template: bool,
+    /// 空值视为错误: 收集全部空值后删除导出目录并返回错误，默认只报告警告
+    pub fail_on_empty: bool,
+    /// 术语表工作表名，值中的 `{{@term}}` 在写入前替换为术语表中该语言的值
+    pub glossary_sheet: Option<String>,
+    /// 低内存模式: 逐个语言处理并写出，用于数万行的大工作簿
+    pub low_memory: bool,
+    /// 空值使用 base_language (或语言配置的回退链) 中同一个 key 的值代替
+    pub fallback_to_base: bool,
+    /// 上一次导出的文件夹 (文件名模式相同，不能按工作表拆分)，指定后各语言文件只包含新增或值有变化的 key
+    pub since_dir: Option<String>,
+    /// key 命名规范检查: "snake" / "camel" / "kebab"，不填或 "none" 时不检查
+    pub key_naming: Option<String>,
+    /// 诊断模式: 问题提示中注明该单元格是否由公式计算得出
+    pub formula_diagnostics: bool,
+    /// 每个语言都必须存在且不为空的点分 key，有缺失时导出失败
+    pub required_keys: Option<Vec<String>>,
+    /// 必需 key 列表文件，每行一个 key，空行和 `#` 开头的行忽略；与 required_keys 合并
+    pub required_keys_file: Option<String>,
+    /// 译者说明所在列的表头名称 (如 `#context`)，不填时不导出说明
+    pub context_column: Option<String>,
+    /// 说明的写法: "inline" (默认，同级的 `<key>_comment`) / "file" (comments.json)
+    pub comment_style: Option<String>,
+    /// 读取单元格批注并按 key 写入 notes.json，只支持 xlsx / xlsm
+    pub extract_comments: bool,
+    /// 逐行发送问题日志，默认每个工作表汇总为一条 (report.json 中照常有完整明细)
+    pub verbose: bool,
+    /// 所有语言合并写入一个 all.json，代替每个语言单独的 JSON 文件 (其他格式照常输出)
+    pub combined: bool,
+    /// all.json 的结构: "by_key" (默认) / "by_language"
+    pub combined_shape: Option<String>,
+    /// 状态列的表头名称 (如 `status`)，指定后只导出状态在 include_statuses 中的行
+    pub status_column: Option<String>,
+    /// 允许导出的状态，不区分大小写，如 `["approved"]`
+    pub include_statuses: Vec<String>,
+    /// 额外写入 checksums.sha256，列出每个导出文件的 SHA-256 (会一起打包进 zip)
+    pub emit_checksums: bool,
+}
 
-            for (row_idx, row) in range.rows().enumerate().skip(1) {
-                let key = get_cell_string(&row[0]);
-                if key.is_empty() {
-                    continue;
-                }
+/// 复数 key 的输出方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum PluralFormat {
+    I18next,
+    Nested,
+}
 
-                let value = if row.len() > lang_col {
-                    get_cell_string(&row[lang_col])
-                } else {
-                    String::new()
-                };
+fn parse_plural_format(value: Option<&str>) -> Result<Option<PluralFormat>, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") => Ok(None),
+        Some("i18next") => Ok(Some(PluralFormat::I18next)),
+        Some("nested") => Ok(Some(PluralFormat::Nested)),
+        Some(other) => Err(format!("不支持的复数格式: {}", other)),
+    }
+}
 
-                if value.is_empty() {
-                    send_progress(
-                        &app,
-                        &format!(
-                            "空值警告 Sheet: '{}' 行: {} 列: '{}' Key: '{}'",
-                            sheet_config.name,
-                            row_idx + 1,
-                            lang_config.code,
-                            key
-                        ),
-                        LogType::Warning,
-                    )?;
-                }
+/// 最终 JSON 的嵌套方式
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum OutputNesting {
+    /// 按工作表类型: nested 工作表展开点分 key，其余保持原样
+    #[default]
+    BySheet,
+    Nested,
+    Flat,
+}
 
-                if let Err(err) = check_placeholders(&value) {
-                    let msg = format!(
-                        "占位符校验失败 Sheet: '{}' 行: {} Key: '{}' 值: '{}' 错误: {}",
-                        sheet_config.name,
-                        row_idx + 1,
-                        key,
-                        value,
-                        err
-                    );
-                    let _ = fs::remove_dir_all(&output_dir);
-                    return Err(msg);
-                }
+fn parse_output_nesting(value: Option<&str>) -> Result<OutputNesting, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") => Ok(OutputNesting::BySheet),
+        Some("nested") => Ok(OutputNesting::Nested),
+        Some("flat") => Ok(OutputNesting::Flat),
+        Some(other) => Err(format!("不支持的输出结构: {}", other)),
+    }
+}
 
-                temp.insert(key, value);
-            }
+impl Default for ConvertOptions {
+    fn default() -> Self {
+        Self {
+            output_format: "json".to_string(),
+            validate_only: false,
+            output_dir: None,
+            base_language: None,
+            compression: "deflated".to_string(),
+            compression_level: None,
+            skip_zip: false,
+            archive_format: None,
+            incremental: false,
+            timestamped: true,
+            folder_name: None,
+            overwrite: false,
+            zip_path: None,
+            trim: false,
+            normalize_newlines: false,
+            preserve_types: false,
+            only_languages: None,
+            fill_merged_keys: false,
+            lang_config_sheet: None,
+            sheet_config_sheet: None,
+            config_file: None,
+            password: None,
+            auto_languages: false,
+            key_column: None,
+            omit_empty: false,
+            treat_blank_as_empty: true,
+            skip_empty_languages: false,
+            output_nesting: None,
+            sort_keys: false,
+            allow_single_braces: false,
+            placeholder_delimiters: ("{{".to_string(), "}}".to_string()),
+            placeholder_style: None,
+            split_by_sheet: false,
+            root_file_name: None,
+            root_wrapper: None,
+            filename_pattern: None,
+            layout: None,
+            plural_format: None,
+            write_bom: false,
+            encoding: None,
+            indent: 2,
+            properties_utf8: false,
+            trim_keys: false,
+            clean_invisible: false,
+            max_value_length: None,
+            duplicate_value_min_length: None,
+            duplicate_value_allowlist: Vec::new(),
+            strict: false,
+            rtl_marks: None,
+            emit_meta: false,
+            emit_schema: false,
+            emit_template: false,
+            schema_patterns: false,
+            fail_on_empty: false,
+            glossary_sheet: None,
+            low_memory: false,
+            fallback_to_base: false,
+            since_dir: None,
+            key_naming: None,
+            formula_diagnostics: false,
+            required_keys: None,
+            required_keys_file: None,
+            context_column: None,
+            comment_style: None,
+            extract_comments: false,
+            verbose: false,
+            combined: false,
+            combined_shape: None,
+            status_column: None,
+            include_statuses: Vec::new(),
+            emit_checksums: false,
+        }
+    }
+}
 
-            sheet_data_map.insert(sheet_config.name.clone(), temp);
+/// 将 JsonValue 转为 YAML 值，对象的 key 顺序保持不变
+fn json_to_yaml(value: &JsonValue) -> serde_yaml::Value {
+    match value {
+        JsonValue::Null => serde_yaml::Value::Null,
+        JsonValue::Short(s) => serde_yaml::Value::String(s.to_string()),
+        JsonValue::String(s) => serde_yaml::Value::String(s.clone()),
+        JsonValue::Number(n) => {
+            let f = f64::from(*n);
+            // 整数不写成 `7.0`
+            let number = if f.fract() == 0.0 && f.abs() < i64::MAX as f64 {
+                serde_yaml::Number::from(f as i64)
+            } else {
+                serde_yaml::Number::from(f)
+            };
+            serde_yaml::Value::Number(number)
+        }
+        JsonValue::Boolean(b) => serde_yaml::Value::Bool(*b),
+        JsonValue::Object(obj) => {
+            let mut mapping = serde_yaml::Mapping::new();
+            for (k, v) in obj.iter() {
+                mapping.insert(serde_yaml::Value::String(k.to_string()), json_to_yaml(v));
+            }
+            serde_yaml::Value::Mapping(mapping)
+        }
+        JsonValue::Array(arr) => {
+            serde_yaml::Value::Sequence(arr.iter().map(json_to_yaml).collect())
         }
+    }
+}
 
-        // 合并 sheet 数据
-        let mut final_json = JsonValue::new_object();
-        for sheet_config in &sheet_configs {
-            if let Some(temp) = sheet_data_map.get(&sheet_config.name) {
-                if sheet_config.sheet_type.as_deref() == Some("root") {
-                    for (k, v) in temp {
-                        final_json[k] = v.clone().into();
-                    }
-                } else {
-                    let mut sheet_obj = JsonValue::new_object();
-                    for (k, v) in temp {
-                        sheet_obj[k] = v.clone().into();
-                    }
-                    final_json[sheet_config.name.clone()] = sheet_obj;
-                }
+/// 将 JsonValue 转为 TOML 值，嵌套对象对应 TOML 表；
+/// 所有标量都写成字符串，避免数字形式的值被当作 TOML 数字
+fn json_to_toml(value: &JsonValue) -> toml::Value {
+    match value {
+        JsonValue::Object(obj) => {
+            let mut table = toml::Table::new();
+            for (k, v) in obj.iter() {
+                table.insert(k.to_string(), json_to_toml(v));
             }
+            toml::Value::Table(table)
+        }
+        JsonValue::Array(arr) => toml::Value::Array(arr.iter().map(json_to_toml).collect()),
+        JsonValue::Null => toml::Value::String(String::new()),
+        JsonValue::String(_) | JsonValue::Short(_) => {
+            toml::Value::String(value.as_str().unwrap_or_default().to_string())
         }
+        other => toml::Value::String(other.dump()),
+    }
+}
 
-        // 写入文件
-        let json_str = final_json.pretty(2);
-        let output_path = output_dir.join(format!("{}.json", lang_config.code));
-        fs::write(&output_path, json_str).map_err(|e| e.to_string())?;
-        send_progress(
-            &app,
-            &format!("✅ 已导出语言文件: {}", output_path.display()),
-            LogType::Success,
-        )?;
-        all_jsons.push(output_path);
+/// 生成 CSV 文本，csv crate 负责处理逗号、引号和换行的转义
+fn write_csv_rows<'a>(rows: impl Iterator<Item = Vec<&'a str>>) -> Result<String, String> {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    for row in rows {
+        writer
+            .write_record(row)
+            .map_err(|e| format!("生成 CSV 失败: {}", e))?;
     }
+    let bytes = writer
+        .into_inner()
+        .map_err(|e| format!("生成 CSV 失败: {}", e))?;
+    String::from_utf8(bytes).map_err(|e| format!("生成 CSV 失败: {}", e))
+}
 
-    // 压缩导出文件夹
-    let zip_path = output_dir.with_extension("zip");
-    send_progress(&app, "正在压缩导出文件夹...", LogType::Info)?;
-    zip_directory(&output_dir, &zip_path)?;
-    send_progress(
-        &app,
-        &format!("✅ 已压缩文件夹为: {}", zip_path.display()),
-        LogType::Success,
-    )?;
+/// 单个语言的 CSV: key,value 两列，嵌套 key 展开为带点形式
+fn to_csv(value: &JsonValue) -> Result<String, String> {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let header = std::iter::once(vec!["key", "value"]);
+    write_csv_rows(header.chain(flat.iter().map(|(k, v)| vec![k.as_str(), v.as_str()])))
+}
 
-    // 删除原始文件夹
-    if let Err(e) = fs::remove_dir_all(&output_dir) {
-        send_progress(&app, &format!("⚠️ 删除文件夹失败: {}", e), LogType::Warning)?;
+/// 所有语言合并的 CSV: key 列 + 每个语言一列，key 顺序按首次出现的顺序
+fn to_combined_csv(languages: &[(String, JsonValue)]) -> Result<String, String> {
+    let flats: Vec<IndexMap<String, String>> = languages
+        .iter()
+        .map(|(_, value)| {
+            let mut flat = IndexMap::new();
+            flatten_json(value, "", &mut flat);
+            flat
+        })
+        .collect();
+    let keys: IndexSet<&str> = flats
+        .iter()
+        .flat_map(|flat| flat.keys().map(|k| k.as_str()))
+        .collect();
+
+    let header = std::iter::once("key")
+        .chain(languages.iter().map(|(code, _)| code.as_str()))
+        .collect();
+    let rows = keys.iter().map(|key| {
+        std::iter::once(*key)
+            .chain(
+                flats
+                    .iter()
+                    .map(|flat| flat.get(*key).map(|v| v.as_str()).unwrap_or_default()),
+            )
+            .collect()
+    });
+    write_csv_rows(std::iter::once(header).chain(rows))
+}
+
+/// Android 的资源目录名: `en` -> `values-en`，`zh-CN` / `zh_CN` -> `values-zh-rCN`
+fn android_values_dir(code: &str) -> String {
+    match code.split_once(['-', '_']) {
+        Some((lang, region)) => format!("values-{}-r{}", lang, region.to_uppercase()),
+        None => format!("values-{}", code),
     }
+}
 
-    Ok(format!(
-        "完成导出 {} 个语言文件并已压缩为 {:?}",
-        all_jsons.len(),
-        zip_path
-    ))
+/// Android 资源名只能包含字母、数字和下划线，嵌套 key 的点也转为下划线
+fn android_resource_name(key: &str) -> String {
+    key.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
 }
 
-#[cfg_attr(mobile, tauri::mobile_entry_point)]
-pub fn run() {
-    tauri::Builder::default()
+/// 按 Android 字符串资源规则转义: XML 实体、引号、撇号、换行以及开头的 @ / ?
+fn escape_android_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, ch) in value.chars().enumerate() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\'' => escaped.push_str("\\'"),
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '@' | '?' if i == 0 => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// 单个语言的 Android strings.xml
+fn to_android_xml(value: &JsonValue) -> String {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<resources>\n");
+    for (key, value) in &flat {
+        xml.push_str(&format!(
+            "    <string name=\"{}\">{}</string>\n",
+            android_resource_name(&key.replace("\\.", ".")),
+            escape_android_string(value)
+        ));
+    }
+    xml.push_str("</resources>\n");
+    xml
+}
+
+/// 按 Apple .strings 语法转义双引号、反斜杠和换行
+fn escape_ios_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// 单个语言的 iOS Localizable.strings，嵌套 key 展开为点分 key
+fn to_ios_strings(value: &JsonValue) -> String {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let mut strings = String::new();
+    for (key, value) in &flat {
+        strings.push_str(&format!(
+            "\"{}\" = \"{}\";\n",
+            escape_ios_string(&key.replace("\\.", ".")),
+            escape_ios_string(value)
+        ));
+    }
+    strings
+}
+
+/// 复数组对应的 iOS Localizable.stringsdict
+fn to_ios_stringsdict(groups: &IndexMap<String, IndexMap<String, String>>) -> String {
+    let escape = |s: &str| {
+        s.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+    };
+    let mut plist = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE plist PUBLIC \"-//Apple//DTD PLIST 1.0//EN\" \"http://www.apple.com/DTDs/PropertyList-1.0.dtd\">\n<plist version=\"1.0\">\n<dict>\n",
+    );
+    for (key, categories) in groups {
+        plist.push_str(&format!("    <key>{}</key>\n    <dict>\n", escape(key)));
+        plist.push_str(
+            "        <key>NSStringLocalizedFormatKey</key>\n        <string>%#@value@</string>\n",
+        );
+        plist.push_str("        <key>value</key>\n        <dict>\n");
+        plist.push_str("            <key>NSStringFormatSpecTypeKey</key>\n            <string>NSStringPluralRuleType</string>\n");
+        plist.push_str(
+            "            <key>NSStringFormatValueTypeKey</key>\n            <string>d</string>\n",
+        );
+        for (category, value) in categories {
+            plist.push_str(&format!(
+                "            <key>{}</key>\n            <string>{}</string>\n",
+                category,
+                escape(value)
+            ));
+        }
+        plist.push_str("        </dict>\n    </dict>\n");
+    }
+    plist.push_str("</dict>\n</plist>\n");
+    plist
+}
+
+/// 按 java.util.Properties 的语法转义: 反斜杠、`=`、`:`、`#`、`!`、控制字符，
+/// key 中的空格和值开头的空格；`unicode` 为 true 时非 ASCII 字符写成 `\uXXXX`
+fn escape_properties(value: &str, is_key: bool, unicode: bool) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for (i, ch) in value.chars().enumerate() {
+        match ch {
+            '\\' | '=' | ':' | '#' | '!' => {
+                escaped.push('\\');
+                escaped.push(ch);
+            }
+            ' ' if is_key || i == 0 => escaped.push_str("\\ "),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            '\u{c}' => escaped.push_str("\\f"),
+            c if unicode && !c.is_ascii() => {
+                // 超出 BMP 的字符按 UTF-16 代理对写两个 \uXXXX
+                let mut units = [0u16; 2];
+                for unit in c.encode_utf16(&mut units) {
+                    escaped.push_str(&format!("\\u{:04X}", unit));
+                }
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// 单个语言的 Java .properties，嵌套 key 展开为点分 key，按导出顺序排列
+fn to_properties(value: &JsonValue, utf8: bool) -> String {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let mut properties = String::new();
+    for (key, value) in &flat {
+        properties.push_str(&format!(
+            "{}={}\n",
+            escape_properties(&key.replace("\\.", "."), true, !utf8),
+            escape_properties(value, false, !utf8)
+        ));
+    }
+    properties
+}
+
+/// 按 PO 语法转义双引号、反斜杠和控制字符
+fn escape_po_string(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+        .replace('\t', "\\t")
+}
+
+/// 单个语言的 Flutter .arb 文件。ARB 的 key 需要是合法的 Dart 标识符，嵌套 key 用下划线连接；
+/// 占位符 (默认 `{{name}}`) 改写为 `{name}` 并在 `@key.placeholders` 中声明，说明列的内容写入 `@key.description`
+fn to_arb(
+    value: &JsonValue,
+    code: &str,
+    notes: &KeyNotes,
+    indent: u16,
+    syntax: &PlaceholderSyntax,
+) -> String {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let mut arb = JsonValue::new_object();
+    arb["@@locale"] = code.replace('-', "_").into();
+    for (key, value) in &flat {
+        let dotted = key.replace("\\.", ".");
+        // inline 说明生成的 `<key>_comment` 已经写入 description
+        if dotted
+            .strip_suffix("_comment")
+            .is_some_and(|k| notes.descriptions.contains_key(k))
+        {
+            continue;
+        }
+        let name = dotted.replace('.', "_");
+        arb[name.as_str()] = syntax.name_re.replace_all(value, "{$1}").as_ref().into();
+
+        let mut meta = JsonValue::new_object();
+        if let Some(description) = notes.descriptions.get(&dotted) {
+            meta["description"] = description.as_str().into();
+        }
+        let mut placeholders = JsonValue::new_object();
+        for caps in syntax.name_re.captures_iter(value) {
+            placeholders[&caps[1]] = json::object! { "type": "String" };
+        }
+        if !placeholders.is_empty() {
+            meta["placeholders"] = placeholders;
+        }
+        if !meta.is_empty() {
+            arb[format!("@{}", name)] = meta;
+        }
+    }
+    match indent {
+        0 => arb.dump(),
+        indent => arb.pretty(indent),
+    }
+}
+
+/// 单个语言的 gettext .po 文件: msgid 为 key，msgstr 为译文，
+/// `sources` 为点分 key -> "工作表:行号"，写成 `#:` 引用注释
+fn to_po(value: &JsonValue, code: &str, sources: &HashMap<String, String>) -> String {
+    let mut flat = IndexMap::new();
+    flatten_json(value, "", &mut flat);
+    let mut po = format!(
+        "msgid \"\"\nmsgstr \"\"\n\"Language: {}\\n\"\n\"MIME-Version: 1.0\\n\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\"Content-Transfer-Encoding: 8bit\\n\"\n",
+        escape_po_string(code)
+    );
+    for (key, value) in &flat {
+        po.push('\n');
+        let msgid = key.replace("\\.", ".");
+        if let Some(source) = sources.get(&msgid) {
+            po.push_str(&format!("#: {}\n", source));
+        }
+        po.push_str(&format!(
+            "msgid \"{}\"\nmsgstr \"{}\"\n",
+            escape_po_string(&msgid),
+            escape_po_string(value)
+        ));
+    }
+    po
+}
+
+/// 按合并后的点分 key 记录的附加信息，只有部分格式使用
+#[derive(Default)]
+struct KeyNotes {
+    /// 点分 key -> "工作表:行号"，PO 格式写成 `#:` 引用注释
+    sources: HashMap<String, String>,
+    /// 点分 key -> 说明列的内容，ARB 格式写成 description
+    descriptions: HashMap<String, String>,
+}
+
+impl KeyNotes {
+    /// 所有 key 加上前缀，用于 root_wrapper 包装后的输出
+    fn with_prefix(&self, prefix: &str) -> KeyNotes {
+        let add = |map: &HashMap<String, String>| {
+            map.iter()
+                .map(|(k, v)| (format!("{}{}", prefix, k), v.clone()))
+                .collect()
+        };
+        KeyNotes {
+            sources: add(&self.sources),
+            descriptions: add(&self.descriptions),
+        }
+    }
+
+    /// 只保留以 `prefix` 开头的 key 并去掉前缀，用于按工作表拆分后的单个文件
+    fn strip_prefix(&self, prefix: &str) -> KeyNotes {
+        let strip = |map: &HashMap<String, String>| {
+            map.iter()
+                .filter_map(|(k, v)| Some((k.strip_prefix(prefix)?.to_string(), v.clone())))
+                .collect()
+        };
+        KeyNotes {
+            sources: strip(&self.sources),
+            descriptions: strip(&self.descriptions),
+        }
+    }
+}
+
+/// 按指定格式序列化单个语言的最终结果，`notes` 见 KeyNotes
+fn serialize_output(
+    value: &JsonValue,
+    format: OutputFormat,
+    code: &str,
+    notes: &KeyNotes,
+    options: &ConvertOptions,
+    syntax: &PlaceholderSyntax,
+) -> Result<String, String> {
+    match format {
+        OutputFormat::Json if options.indent == 0 => Ok(value.dump()),
+        OutputFormat::Json => Ok(value.pretty(options.indent)),
+        OutputFormat::Yaml => serde_yaml::to_string(&json_to_yaml(value))
+            .map_err(|e| format!("生成 YAML 失败: {}", e)),
+        OutputFormat::Csv => to_csv(value),
+        OutputFormat::Android => Ok(to_android_xml(value)),
+        OutputFormat::Ios => Ok(to_ios_strings(value)),
+        OutputFormat::Po => Ok(to_po(value, code, &notes.sources)),
+        OutputFormat::Toml => {
+            toml::to_string(&json_to_toml(value)).map_err(|e| format!("生成 TOML 失败: {}", e))
+        }
+        OutputFormat::Properties => Ok(to_properties(value, options.properties_utf8)),
+        OutputFormat::Arb => Ok(to_arb(value, code, notes, options.indent, syntax)),
+        OutputFormat::CsvCombined => Err("合并格式不能按单个语言输出".to_string()),
+    }
+}
+
+/// 从 Word 等处粘贴进来的不可见字符: 控制字符 (换行和制表符除外)、零宽字符、BOM 和不换行空格。
+/// 零宽连接符 (U+200C / U+200D) 在波斯语、印度语系文字和组合 emoji 中是正常字符，只在 key 中检查
+fn is_invisible_char(c: char, in_key: bool) -> bool {
+    match c {
+        '\n' | '\r' | '\t' => in_key,
+        '\u{200B}' | '\u{2060}' | '\u{FEFF}' => true,
+        '\u{200C}' | '\u{200D}' => in_key,
+        c => c.is_control() || is_nbsp(c),
+    }
+}
+
+fn is_nbsp(c: char) -> bool {
+    matches!(c, '\u{00A0}' | '\u{2007}' | '\u{202F}')
+}
+
+/// 文本中出现的不可见字符，按首次出现的顺序去重，格式如 `U+200B, U+00A0`
+fn find_invisible_chars(text: &str, in_key: bool) -> Option<String> {
+    let mut found: Vec<char> = Vec::new();
+    for c in text.chars().filter(|c| is_invisible_char(*c, in_key)) {
+        if !found.contains(&c) {
+            found.push(c);
+        }
+    }
+    if found.is_empty() {
+        return None;
+    }
+    let codepoints: Vec<String> = found
+        .iter()
+        .map(|c| format!("U+{:04X}", *c as u32))
+        .collect();
+    Some(codepoints.join(", "))
+}
+
+/// 删除不可见字符，不换行空格换成普通空格以免前后的词连在一起
+fn clean_invisible_chars(text: &str, in_key: bool) -> String {
+    text.chars()
+        .filter_map(|c| match c {
+            c if is_nbsp(c) => Some(' '),
+            c if is_invisible_char(c, in_key) => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+/// 按选项对单元格值做后处理，选项都关闭时原样返回
+fn normalize_value(value: String, options: &ConvertOptions) -> String {
+    let mut value = value;
+    if options.normalize_newlines && value.contains('\r') {
+        value = value.replace("\r\n", "\n").replace('\r', "\n");
+    }
+    if options.trim {
+        let trimmed = value.trim();
+        if trimmed.len() != value.len() {
+            value = trimmed.to_string();
+        }
+    }
+    value
+}
+
+/// 按占位符风格检查写法是否正确，并报告混用的其他风格的占位符
+fn check_placeholders(
+    value: &str,
+    allow_single_braces: bool,
+    syntax: &PlaceholderSyntax,
+) -> Result<(), String> {
+    // 术语引用在校验之后才替换，其他风格下换成等长的空格，保持报告中的字符位置不变
+    let masked;
+    let value = if syntax.style == PlaceholderStyle::DoubleBrace {
+        value
+    } else {
+        masked = GLOSSARY_REF_RE.replace_all(value, |caps: &regex::Captures| {
+            " ".repeat(caps[0].chars().count())
+        });
+        masked.as_ref()
+    };
+    static DOUBLE_BRACE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\{\{\s*\w+\s*\}\}").unwrap());
+    static SINGLE_BRACE_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"\{\s*\w+\s*\}").unwrap());
+    let stray = |re: &Regex, style: PlaceholderStyle| match re.find(value) {
+        Some(m) => Err(format!(
+            "第 {} 个字符处的 '{}' 是 {} 风格的占位符, 当前风格为 {}",
+            value[..m.start()].chars().count() + 1,
+            m.as_str(),
+            style.name(),
+            syntax.style.name()
+        )),
+        None => Ok(()),
+    };
+    match syntax.style {
+        PlaceholderStyle::DoubleBrace => {
+            check_delimited_placeholders(value, allow_single_braces, syntax)?
+        }
+        PlaceholderStyle::SingleBrace => {
+            stray(&DOUBLE_BRACE_RE, PlaceholderStyle::DoubleBrace)?;
+            check_delimited_placeholders(value, allow_single_braces, syntax)?;
+        }
+        PlaceholderStyle::Printf => {
+            stray(&DOUBLE_BRACE_RE, PlaceholderStyle::DoubleBrace)?;
+            stray(&SINGLE_BRACE_RE, PlaceholderStyle::SingleBrace)?;
+        }
+        PlaceholderStyle::Icu => {
+            stray(&DOUBLE_BRACE_RE, PlaceholderStyle::DoubleBrace)?;
+            check_icu_message(value)?;
+        }
+    }
+    // 默认风格保持原有的校验，`%s` 等只在显式选择了其他风格时才视为混用
+    if matches!(
+        syntax.style,
+        PlaceholderStyle::SingleBrace | PlaceholderStyle::Icu
+    ) {
+        if let Some((pos, token)) = find_printf_token(value) {
+            return Err(format!(
+                "第 {} 个字符处的 '{}' 是 printf 风格的占位符, 当前风格为 {}",
+                pos + 1,
+                token,
+                syntax.style.name()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 返回第一个 printf 风格占位符的字符位置和内容。`%%` 是转义的百分号；
+/// 后面紧跟字母的 (如 `100%success`) 视为普通文本
+fn find_printf_token(value: &str) -> Option<(usize, &str)> {
+    static PRINTF_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r"%%|%(?:\d+\$)?[-+0#]*\d*(?:\.\d+)?(?:hh|h|ll|l|z)?[sdiufxXoeEgGc@]").unwrap()
+    });
+    PRINTF_RE
+        .find_iter(value)
+        .filter(|m| m.as_str() != "%%")
+        .find(|m| {
+            !value[m.end()..]
+                .chars()
+                .next()
+                .is_some_and(|c| c.is_ascii_alphabetic())
+        })
+        .map(|m| (value[..m.start()].chars().count(), m.as_str()))
+}
+
+/// 按配置的分隔符 (默认 `{{name}}`) 解析占位符，检查写法是否正确:
+/// 未闭合 (`{{a}`)、空占位符 (`{{}}`)、嵌套 (`{{{{a}}}}`) 都会报错；
+/// 占位符之外的单个括号 (`{a}`、`{}`) 只有 `allow_single_braces` 为 true 时才视为字面量
+fn check_delimited_placeholders(
+    value: &str,
+    allow_single_braces: bool,
+    syntax: &PlaceholderSyntax,
+) -> Result<(), String> {
+    let chars: Vec<char> = value.chars().collect();
+    let open: Vec<char> = syntax.open.chars().collect();
+    let close: Vec<char> = syntax.close.chars().collect();
+    let at = |i: usize, delimiter: &[char]| chars.get(i..i + delimiter.len()) == Some(delimiter);
+    let mut i = 0;
+    while i < chars.len() {
+        if at(i, &open) {
+            let mut end = i + open.len();
+            loop {
+                if end >= chars.len() {
+                    return Err(format!("第 {} 个字符处的占位符没有闭合", i + 1));
+                }
+                if at(end, &close) {
+                    break;
+                }
+                if at(end, &open) || syntax.open_bracket == Some(chars[end]) {
+                    return Err(format!(
+                        "第 {} 个字符处的占位符嵌套了 '{}'",
+                        i + 1,
+                        chars[end]
+                    ));
+                }
+                end += 1;
+            }
+            let name: String = chars[i + open.len()..end].iter().collect();
+            if name.trim().is_empty() {
+                return Err(format!("第 {} 个字符处的占位符为空", i + 1));
+            }
+            i = end + close.len();
+        } else if syntax.brackets.contains(&chars[i]) && !allow_single_braces {
+            return Err(format!("第 {} 个字符处有单独的括号 '{}'", i + 1, chars[i]));
+        } else {
+            i += 1;
+        }
+    }
+    Ok(())
+}
+
+/// 检查 ICU MessageFormat 的写法: 括号配对、参数名和类型，
+/// plural / selectordinal / select 的每个分支都要有 `{...}` 子消息且必须有 other 分支。
+/// `'{'` 这样的单引号转义按字面量处理
+fn check_icu_message(value: &str) -> Result<(), String> {
+    let chars: Vec<char> = value.chars().collect();
+    let mut i = 0;
+    parse_icu_text(&chars, &mut i, false)
+}
+
+/// 解析消息文本。子消息 (`nested`) 遇到未配对的 `}` 时停在该位置返回
+fn parse_icu_text(chars: &[char], i: &mut usize, nested: bool) -> Result<(), String> {
+    while *i < chars.len() {
+        match chars[*i] {
+            '\'' if chars.get(*i + 1) == Some(&'\'') => *i += 2,
+            '\'' if matches!(chars.get(*i + 1), Some('{' | '}' | '#')) => {
+                *i += 1;
+                while *i < chars.len() && chars[*i] != '\'' {
+                    *i += 1;
+                }
+                *i += 1;
+            }
+            '{' => parse_icu_argument(chars, i)?,
+            '}' if nested => return Ok(()),
+            '}' => return Err(format!("第 {} 个字符处有单独的括号 '}}'", *i + 1)),
+            _ => *i += 1,
+        }
+    }
+    Ok(())
+}
+
+/// 读取到 `,` 或 `}` 之前的内容 (不含)，遇到 `{` 或末尾时停下
+fn read_icu_token(chars: &[char], i: &mut usize) -> String {
+    let start = *i;
+    while *i < chars.len() && !matches!(chars[*i], ',' | '{' | '}') {
+        *i += 1;
+    }
+    chars[start..*i]
+        .iter()
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// 解析从 `{` 开始的参数，结束时停在对应的 `}` 之后
+fn parse_icu_argument(chars: &[char], i: &mut usize) -> Result<(), String> {
+    let start = *i + 1;
+    let unclosed = || format!("第 {} 个字符处的占位符没有闭合", start);
+    *i += 1;
+    let name = read_icu_token(chars, i);
+    match chars.get(*i) {
+        None => return Err(unclosed()),
+        Some('{') => return Err(format!("第 {} 个字符处的占位符嵌套了 '{{'", start)),
+        _ => {}
+    }
+    if name.is_empty() {
+        return Err(format!("第 {} 个字符处的占位符为空", start));
+    }
+    if !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(format!("第 {} 个字符处的占位符名称 '{}' 无效", start, name));
+    }
+    if chars[*i] == '}' {
+        *i += 1;
+        return Ok(());
+    }
+
+    *i += 1;
+    let kind = read_icu_token(chars, i);
+    match kind.as_str() {
+        "number" | "date" | "time" | "spellout" | "ordinal" | "duration" => {
+            if chars.get(*i) == Some(&',') {
+                *i += 1;
+                read_icu_token(chars, i);
+            }
+            if chars.get(*i) != Some(&'}') {
+                return Err(unclosed());
+            }
+            *i += 1;
+            Ok(())
+        }
+        "plural" | "selectordinal" | "select" => {
+            if chars.get(*i) != Some(&',') {
+                return Err(format!("第 {} 个字符处的 {} 缺少分支", start, kind));
+            }
+            *i += 1;
+            let mut selectors = Vec::new();
+            loop {
+                while chars.get(*i).is_some_and(|c| c.is_whitespace()) {
+                    *i += 1;
+                }
+                match chars.get(*i) {
+                    None => return Err(unclosed()),
+                    Some('}') => break,
+                    _ => {}
+                }
+                let selector_start = *i;
+                while chars
+                    .get(*i)
+                    .is_some_and(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | ','))
+                {
+                    *i += 1;
+                }
+                let selector: String = chars[selector_start..*i].iter().collect();
+                if kind != "select" && selector.starts_with("offset:") {
+                    continue;
+                }
+                let valid = if kind == "select" {
+                    !selector.is_empty()
+                        && selector.chars().all(|c| c.is_alphanumeric() || c == '_')
+                } else {
+                    PLURAL_CATEGORIES.contains(&selector.as_str())
+                        || selector
+                            .strip_prefix('=')
+                            .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+                };
+                if !valid {
+                    return Err(format!(
+                        "第 {} 个字符处的 {} 分支 '{}' 无效",
+                        selector_start + 1,
+                        kind,
+                        selector
+                    ));
+                }
+                while chars.get(*i).is_some_and(|c| c.is_whitespace()) {
+                    *i += 1;
+                }
+                if chars.get(*i) != Some(&'{') {
+                    return Err(format!(
+                        "第 {} 个字符处的 {} 分支 '{}' 后缺少 '{{'",
+                        selector_start + 1,
+                        kind,
+                        selector
+                    ));
+                }
+                let message_start = *i + 1;
+                *i += 1;
+                parse_icu_text(chars, i, true)?;
+                if *i >= chars.len() {
+                    return Err(format!("第 {} 个字符处的子消息没有闭合", message_start));
+                }
+                *i += 1;
+                selectors.push(selector);
+            }
+            *i += 1;
+            if !selectors.iter().any(|s| s == "other") {
+                return Err(format!("第 {} 个字符处的 {} 缺少 other 分支", start, kind));
+            }
+            Ok(())
+        }
+        "" => Err(format!("第 {} 个字符处的占位符类型为空", start)),
+        other => Err(format!(
+            "第 {} 个字符处的占位符类型 '{}' 无效",
+            start, other
+        )),
+    }
+}
+
+/// 按 `.` 拆分嵌套 key，`\.` 会被保留为字面量的点
+fn split_dotted_key(dotted_key: &str) -> Vec<String> {
+    let mut parts = Vec::new();
+    let mut current = String::new();
+    let mut chars = dotted_key.chars().peekable();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' if chars.peek() == Some(&'.') => {
+                current.push('.');
+                chars.next();
+            }
+            '.' => parts.push(std::mem::take(&mut current)),
+            _ => current.push(ch),
+        }
+    }
+    parts.push(current);
+    parts
+}
+
+/// 解析 root_wrapper 的点分路径，不填时返回空列表
+fn parse_root_wrapper(value: Option<&str>) -> Result<Vec<String>, String> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(Vec::new()),
+        Some(path) => {
+            let segments = split_dotted_key(path);
+            if segments.iter().any(|s| s.is_empty()) {
+                return Err(format!("root_wrapper 配置无效: '{}'", path));
+            }
+            Ok(segments)
+        }
+    }
+}
+
+/// 把整个语言的内容逐层放进 root_wrapper 的路径下
+fn wrap_root(value: JsonValue, wrapper: &[String]) -> JsonValue {
+    wrapper.iter().rev().fold(value, |value, segment| {
+        let mut obj = JsonValue::new_object();
+        obj[segment.as_str()] = value;
+        obj
+    })
+}
+
+/// 将带点的 key 插入为嵌套对象，例如 `menu.file.open` -> {"menu":{"file":{"open":"..."}}}
+fn insert_nested_key(
+    obj: &mut JsonValue,
+    dotted_key: &str,
+    value: JsonValue,
+) -> Result<(), String> {
+    let parts = split_dotted_key(dotted_key);
+    if parts.iter().any(|p| p.is_empty()) {
+        return Err(format!("嵌套 key 格式错误: {}", dotted_key));
+    }
+
+    let (last, parents) = parts.split_last().unwrap();
+    let mut current = obj;
+    for (i, part) in parents.iter().enumerate() {
+        if !current.has_key(part) {
+            current[part.as_str()] = JsonValue::new_object();
+        } else if !current[part.as_str()].is_object() {
+            return Err(format!(
+                "key 冲突: '{}' 已经是一个值, 无法再作为 '{}' 的父级",
+                parts[..=i].join("."),
+                dotted_key
+            ));
+        }
+        current = &mut current[part.as_str()];
+    }
+
+    if current.has_key(last) {
+        return Err(format!(
+            "key 冲突: '{}' 已经包含子级, 无法再赋值",
+            dotted_key
+        ));
+    }
+    current[last.as_str()] = value;
+    Ok(())
+}
+
+/// 列类型声明，用于控制数字单元格是否按日期序列号解析
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum ColumnType {
+    /// 未声明：沿用 30000~70000 之间的浮点数视为日期的旧规则
+    #[default]
+    Auto,
+    /// 始终按数字输出
+    Number,
+    /// 始终按日期输出
+    Date,
+    /// 按日期输出，使用各语言配置的日期格式
+    LocalDate,
+    /// 按数字输出，使用各语言配置的小数点和千位分隔符
+    LocalNumber,
+}
+
+impl ColumnType {
+    fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_lowercase().as_str() {
+            "auto" => Some(ColumnType::Auto),
+            "number" => Some(ColumnType::Number),
+            "date" => Some(ColumnType::Date),
+            "local_date" => Some(ColumnType::LocalDate),
+            "local_number" => Some(ColumnType::LocalNumber),
+            _ => None,
+        }
+    }
+}
+
+/// 单元格的值。布尔和数字只在 preserve_types 开启时产生，
+/// 校验和日志都使用 Display 的文本形式，与未开启时的字符串一致
+#[derive(Debug, Clone, PartialEq)]
+enum CellValue {
+    Text(String),
+    Bool(bool),
+    Number(f64),
+}
+
+impl CellValue {
+    fn is_empty(&self) -> bool {
+        matches!(self, CellValue::Text(s) if s.is_empty())
+    }
+
+    fn to_json(&self) -> JsonValue {
+        match self {
+            CellValue::Text(s) => s.as_str().into(),
+            CellValue::Bool(b) => (*b).into(),
+            CellValue::Number(n) => (*n).into(),
+        }
+    }
+}
+
+impl std::fmt::Display for CellValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CellValue::Text(s) => write!(f, "{}", s),
+            CellValue::Bool(b) => write!(f, "{}", b),
+            CellValue::Number(n) => write!(f, "{}", format_number(*n)),
+        }
+    }
+}
+
+/// 每个语言的数据: 工作表名 -> (key -> value)
+type SheetDataMap = IndexMap<String, IndexMap<String, CellValue>>;
+
+/// 占位符风格，决定校验规则
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+enum PlaceholderStyle {
+    /// `{{name}}`，分隔符可由 placeholder_delimiters 修改
+    #[default]
+    DoubleBrace,
+    /// `{name}`
+    SingleBrace,
+    /// `%s`、`%d`、`%1$s`
+    Printf,
+    /// ICU MessageFormat，`{name}` 和 `{count, plural, ...}`
+    Icu,
+}
+
+impl PlaceholderStyle {
+    fn name(self) -> &'static str {
+        match self {
+            PlaceholderStyle::DoubleBrace => "double_brace",
+            PlaceholderStyle::SingleBrace => "single_brace",
+            PlaceholderStyle::Printf => "printf",
+            PlaceholderStyle::Icu => "icu",
+        }
+    }
+}
+
+fn parse_placeholder_style(value: Option<&str>) -> Result<PlaceholderStyle, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("double_brace") => Ok(PlaceholderStyle::DoubleBrace),
+        Some("single_brace") => Ok(PlaceholderStyle::SingleBrace),
+        Some("printf") => Ok(PlaceholderStyle::Printf),
+        Some("icu") => Ok(PlaceholderStyle::Icu),
+        Some(other) => Err(format!("不支持的占位符风格: {}", other)),
+    }
+}
+
+/// 占位符的写法，由 placeholder_style 和 placeholder_delimiters 生成
+struct PlaceholderSyntax {
+    style: PlaceholderStyle,
+    open: String,
+    close: String,
+    /// 起始分隔符的首字符是括号时，占位符内再出现该字符视为嵌套
+    open_bracket: Option<char>,
+    /// 分隔符首尾的括号，在占位符之外单独出现时视为写错
+    brackets: Vec<char>,
+    /// 匹配 `<open>name<close>`，第一个分组为名称
+    name_re: Regex,
+}
+
+impl PlaceholderSyntax {
+    fn new((open, close): &(String, String), style: PlaceholderStyle) -> Result<Self, String> {
+        if style != PlaceholderStyle::DoubleBrace {
+            if open != "{{" || close != "}}" {
+                return Err(format!(
+                    "placeholder_delimiters 只适用于 double_brace 风格, 当前风格为 {}",
+                    style.name()
+                ));
+            }
+            if matches!(style, PlaceholderStyle::SingleBrace | PlaceholderStyle::Icu) {
+                let mut syntax = Self::new(
+                    &("{".to_string(), "}".to_string()),
+                    PlaceholderStyle::DoubleBrace,
+                )?;
+                syntax.style = style;
+                return Ok(syntax);
+            }
+        }
+        if open.trim().is_empty() || close.trim().is_empty() {
+            return Err(format!(
+                "placeholder_delimiters 配置无效: {:?} {:?}, 起止分隔符都不能为空",
+                open, close
+            ));
+        }
+        let is_bracket = |c: &char| "{}[]()<>".contains(*c);
+        let open_bracket = open.chars().next().filter(is_bracket);
+        let brackets = open_bracket
+            .into_iter()
+            .chain(close.chars().last().filter(is_bracket))
+            .collect();
+        let name_re = Regex::new(&format!(
+            r"{}(\w+){}",
+            regex::escape(open),
+            regex::escape(close)
+        ))
+        .map_err(|e| e.to_string())?;
+        Ok(PlaceholderSyntax {
+            style,
+            open: open.clone(),
+            close: close.clone(),
+            open_bracket,
+            brackets,
+            name_re,
+        })
+    }
+
+    /// 按这种写法生成名为 `name` 的占位符
+    fn wrap(&self, name: &str) -> String {
+        format!("{}{}{}", self.open, name, self.close)
+    }
+}
+
+/// 从右到左语言的方向控制符插入方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RtlMarks {
+    /// 每个占位符前后插入 RLM (U+200F)，替换进来的从左到右内容不会打乱两侧标点的顺序
+    Marks,
+    /// 每个占位符用 FSI (U+2068) 和 PDI (U+2069) 包裹，替换内容的方向单独判断
+    Isolate,
+    /// 整个值用 RLE (U+202B) 和 PDF (U+202C) 包裹，强制按从右到左显示
+    Wrap,
+}
+
+fn parse_rtl_marks(value: Option<&str>) -> Result<Option<RtlMarks>, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") => Ok(None),
+        Some("marks") => Ok(Some(RtlMarks::Marks)),
+        Some("isolate") => Ok(Some(RtlMarks::Isolate)),
+        Some("wrap") => Ok(Some(RtlMarks::Wrap)),
+        Some(other) => Err(format!("不支持的 RTL 标记方式: {}", other)),
+    }
+}
+
+/// 插入方向控制符。控制符都在占位符的分隔符之外，不影响占位符校验和名称提取
+fn apply_rtl_marks(value: &str, marks: RtlMarks, syntax: &PlaceholderSyntax) -> String {
+    match marks {
+        RtlMarks::Marks => syntax
+            .name_re
+            .replace_all(value, "\u{200F}$0\u{200F}")
+            .into_owned(),
+        RtlMarks::Isolate => syntax
+            .name_re
+            .replace_all(value, "\u{2068}$0\u{2069}")
+            .into_owned(),
+        RtlMarks::Wrap => format!("\u{202B}{}\u{202C}", value),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum KeyNaming {
+    /// `snake_case`
+    Snake,
+    /// `camelCase`
+    Camel,
+    /// `kebab-case`
+    Kebab,
+}
+
+impl KeyNaming {
+    fn name(self) -> &'static str {
+        match self {
+            KeyNaming::Snake => "snake_case",
+            KeyNaming::Camel => "camelCase",
+            KeyNaming::Kebab => "kebab-case",
+        }
+    }
+
+    /// 纯数字的段 (如 `errors.404`) 在各规范下都允许
+    fn matches(self, segment: &str) -> bool {
+        static SNAKE_RE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^[a-z0-9]+(_[a-z0-9]+)*$").unwrap());
+        static CAMEL_RE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^([a-z][a-zA-Z0-9]*|[0-9]+)$").unwrap());
+        static KEBAB_RE: LazyLock<Regex> =
+            LazyLock::new(|| Regex::new(r"^[a-z0-9]+(-[a-z0-9]+)*$").unwrap());
+        match self {
+            KeyNaming::Snake => SNAKE_RE.is_match(segment),
+            KeyNaming::Camel => CAMEL_RE.is_match(segment),
+            KeyNaming::Kebab => KEBAB_RE.is_match(segment),
+        }
+    }
+}
+
+fn parse_key_naming(value: Option<&str>) -> Result<Option<KeyNaming>, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("none") => Ok(None),
+        Some("snake") => Ok(Some(KeyNaming::Snake)),
+        Some("camel") => Ok(Some(KeyNaming::Camel)),
+        Some("kebab") => Ok(Some(KeyNaming::Kebab)),
+        Some(other) => Err(format!("不支持的 key 命名规范: {}", other)),
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CommentStyle {
+    /// 语言文件中的同级 `<key>_comment`
+    Inline,
+    /// 单独的 comments.json
+    File,
+}
+
+fn parse_comment_style(value: Option<&str>) -> Result<CommentStyle, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("inline") => Ok(CommentStyle::Inline),
+        Some("file") => Ok(CommentStyle::File),
+        Some(other) => Err(format!("不支持的说明写法: {}", other)),
+    }
+}
+
+/// 工作表名 -> key -> 译者说明
+type SheetComments = IndexMap<String, IndexMap<String, String>>;
+
+/// 读取各工作表说明列中的译者说明。说明与语言无关，只读一次；
+/// 没有说明列的工作表和空说明跳过
+fn read_comments(
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    key_column: usize,
+    context_column: &str,
+    options: &ConvertOptions,
+) -> Result<SheetComments, String> {
+    let mut comments = IndexMap::new();
+    for sheet_config in sheet_configs {
+        if comments.contains_key(&sheet_config.name) {
+            continue;
+        }
+        let Some(range) = ranges.get(&sheet_config.name) else {
+            continue;
+        };
+        let Some(header_row) = sheet_config.header(range)? else {
+            continue;
+        };
+        let Some(context_col) = header_row
+            .iter()
+            .position(|c| get_cell_string(c, ColumnType::Auto).trim() == context_column)
+        else {
+            continue;
+        };
+        let mut sheet_comments = IndexMap::new();
+        for (_, row, key) in sheet_keys(range, sheet_config, header_row, key_column, options) {
+            let comment = row
+                .get(context_col)
+                .map(|c| get_cell_string(c, ColumnType::Auto).trim().to_string())
+                .unwrap_or_default();
+            if !comment.is_empty() {
+                sheet_comments.insert(key, comment);
+            }
+        }
+        comments.insert(sheet_config.name.clone(), sheet_comments);
+    }
+    Ok(comments)
+}
+
+/// 工作表名 -> key -> 列名 -> 单元格批注
+type SheetNotes = IndexMap<String, IndexMap<String, IndexMap<String, String>>>;
+
+/// 读取配置的工作表中的单元格批注，按所在行的 key 和所在列的表头归类。
+/// 不在数据行上 (表头、key 为空的行) 的批注跳过；xlsx / xlsm 之外的格式只发出警告
+fn read_cell_notes(
+    progress: &Progress,
+    path: &Path,
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    key_column: usize,
+    options: &ConvertOptions,
+) -> Result<SheetNotes, ExportError> {
+    let (ext, source) = open_workbook_source(path, options.password.as_deref())?;
+    if !matches!(ext.as_str(), "xlsx" | "xlsm") {
+        send_progress(
+            progress,
+            &format!("只支持读取 xlsx / xlsm 的单元格批注, 已跳过: .{}", ext),
+            LogType::Warning,
+        )?;
+        return Ok(SheetNotes::new());
+    }
+    let cell_notes =
+        read_xlsx_comments(source).map_err(|e| format!("读取单元格批注失败: {}", e))?;
+    let mut notes = SheetNotes::new();
+    let mut count = 0;
+    for sheet_config in sheet_configs {
+        if notes.contains_key(&sheet_config.name) {
+            continue;
+        }
+        let (Some(range), Some(sheet_notes)) = (
+            ranges.get(&sheet_config.name),
+            cell_notes.get(&sheet_config.name),
+        ) else {
+            continue;
+        };
+        let Some(header_row) = sheet_config.header(range)? else {
+            continue;
+        };
+        let (start_row, start_col) = range.start().unwrap_or_default();
+        let column_name = |col: usize| {
+            header_row
+                .get(col)
+                .map(|c| get_cell_string(c, ColumnType::Auto).trim().to_string())
+                .filter(|h| !h.is_empty())
+                .unwrap_or_else(|| column_letters(col + start_col as usize))
+        };
+        let mut by_key: IndexMap<String, IndexMap<String, String>> = IndexMap::new();
+        for (row_idx, _, key) in sheet_keys(range, sheet_config, header_row, key_column, options) {
+            let abs_row = row_idx + start_row as usize;
+            for (_, col, note) in sheet_notes.iter().filter(|(r, _, _)| *r == abs_row) {
+                let Some(col) = col.checked_sub(start_col as usize) else {
+                    continue;
+                };
+                by_key
+                    .entry(key.clone())
+                    .or_default()
+                    .insert(column_name(col), note.clone());
+                count += 1;
+            }
+        }
+        notes.insert(sheet_config.name.clone(), by_key);
+    }
+    send_progress(
+        progress,
+        &format!("已读取 {} 条单元格批注", count),
+        LogType::Info,
+    )?;
+    Ok(notes)
+}
+
+/// 列号 (从 0 开始) 转为列字母，如 0 -> "A"、27 -> "AB"
+fn column_letters(col: usize) -> String {
+    let mut letters = Vec::new();
+    let mut n = col + 1;
+    while n > 0 {
+        letters.push(b'A' + ((n - 1) % 26) as u8);
+        n = (n - 1) / 26;
+    }
+    letters.reverse();
+    String::from_utf8(letters).unwrap()
+}
+
+/// 工作表名 -> (行, 列, 批注文本)，行列从 0 开始，按工作表的绝对位置计算
+type XlsxComments = HashMap<String, Vec<(usize, usize, String)>>;
+
+/// 从 xlsx 包中读取各工作表的批注。
+/// calamine 不读取批注，这里按 workbook.xml -> 工作表 rels -> commentsN.xml 的关系查找
+fn read_xlsx_comments<RS: Read + Seek>(source: RS) -> Result<XlsxComments, String> {
+    static COMMENT_RE: LazyLock<Regex> = LazyLock::new(|| {
+        Regex::new(r#"(?s)<comment\b[^>]*\bref="([A-Za-z]+)([0-9]+)"[^>]*>(.*?)</comment>"#)
+            .unwrap()
+    });
+    static TEXT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"(?s)<t\b[^>]*>(.*?)</t>").unwrap());
+    let mut archive = zip::ZipArchive::new(source).map_err(|e| e.to_string())?;
+    let mut read_part = |name: &str| -> Option<String> {
+        let mut file = archive.by_name(name).ok()?;
+        let mut content = String::new();
+        file.read_to_string(&mut content).ok()?;
+        Some(content)
+    };
+    // rels 文件: Id -> (Type, 相对于 `base_dir` 解析后的部件路径)
+    let read_rels = |content: &str, base_dir: &str| -> HashMap<String, (String, String)> {
+        xml_elements_attrs(content, "Relationship")
+            .into_iter()
+            .filter_map(|attrs| {
+                let part = resolve_part_path(base_dir, &unescape_xml(attrs.get("Target")?));
+                Some((
+                    attrs.get("Id")?.to_string(),
+                    (attrs.get("Type")?.to_string(), part),
+                ))
+            })
+            .collect()
+    };
+    let workbook = read_part("xl/workbook.xml").ok_or("缺少 xl/workbook.xml")?;
+    let workbook_rels = read_rels(
+        &read_part("xl/_rels/workbook.xml.rels").unwrap_or_default(),
+        "xl",
+    );
+    let mut comments = HashMap::new();
+    for sheet in xml_elements_attrs(&workbook, "sheet") {
+        let (Some(name), Some((_, sheet_part))) = (
+            sheet.get("name"),
+            sheet.get("r:id").and_then(|id| workbook_rels.get(*id)),
+        ) else {
+            continue;
+        };
+        let (sheet_dir, sheet_file) = sheet_part.rsplit_once('/').unwrap_or(("", sheet_part));
+        let Some(rels) = read_part(&format!("{}/_rels/{}.rels", sheet_dir, sheet_file)) else {
+            continue;
+        };
+        let Some((_, comments_part)) = read_rels(&rels, sheet_dir)
+            .into_values()
+            .find(|(kind, _)| kind.ends_with("/comments"))
+        else {
+            continue;
+        };
+        let Some(content) = read_part(&comments_part) else {
+            continue;
+        };
+        let sheet_comments: Vec<_> = COMMENT_RE
+            .captures_iter(&content)
+            .filter_map(|c| {
+                let col = parse_column_index(&c[1])?;
+                let row = c[2].parse::<usize>().ok()?.checked_sub(1)?;
+                let text: String = TEXT_RE
+                    .captures_iter(&c[3])
+                    .map(|t| unescape_xml(&t[1]))
+                    .collect();
+                let text = text.trim().to_string();
+                (!text.is_empty()).then_some((row, col, text))
+            })
+            .collect();
+        comments.insert(unescape_xml(name), sheet_comments);
+    }
+    Ok(comments)
+}
+
+/// 解析 rels 中的 Target: `/` 开头的为包内绝对路径，否则相对于 `base_dir`，处理 `..`
+fn resolve_part_path(base_dir: &str, target: &str) -> String {
+    let mut parts: Vec<&str> = match target.strip_prefix('/') {
+        Some(_) => Vec::new(),
+        None => base_dir.split('/').filter(|p| !p.is_empty()).collect(),
+    };
+    for segment in target.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            _ => parts.push(segment),
+        }
+    }
+    parts.join("/")
+}
+
+/// 按导出时取 key 的规则逐行读取 key: clean_invisible / trim_keys 时先清理，
+/// fill_merged_keys 时 key 为空的合并单元格沿用上一行的 key，仍为空的行跳过。
+/// 返回 (行序号, 该行, key)，行序号从 0 开始，与 `range.rows()` 一致；
+/// 稀疏的工作表中可能出现比表头短的行，缺少的单元格按空处理
+fn sheet_keys<'a>(
+    range: &'a Range<DataType>,
+    sheet_config: &SheetConfig,
+    header_row: &[DataType],
+    key_column: usize,
+    options: &'a ConvertOptions,
+) -> impl Iterator<Item = (usize, &'a [DataType], String)> + 'a {
+    let key_col = sheet_config.key_column.unwrap_or(key_column);
+    let key_type = sheet_config.column_type(header_row, key_col);
+    let mut last_key = String::new();
+    range
+        .rows()
+        .enumerate()
+        .skip(sheet_config.header_row + 1)
+        .filter_map(move |(row_idx, row)| {
+            let key_cell = row.get(key_col).unwrap_or(&DataType::Empty);
+            let mut key = get_cell_string(key_cell, key_type);
+            if options.clean_invisible {
+                key = clean_invisible_chars(&key, true);
+            }
+            if options.trim_keys {
+                key = key.trim().to_string();
+            }
+            if key.is_empty()
+                && options.fill_merged_keys
+                && *key_cell == DataType::Empty
+                && row.iter().any(|c| *c != DataType::Empty)
+            {
+                key = last_key.clone();
+            }
+            if key.is_empty() {
+                return None;
+            }
+            last_key = key.clone();
+            Some((row_idx, row, key))
+        })
+}
+
+/// 检查 key 列中的不可见字符和首尾空白，每个 key 只报告一次而不是每个语言各报告一次。
+/// 非 verbose 模式下每个工作表的每类问题汇总为一条提示，逐行的明细照常写入报告
+fn check_key_text(
+    progress: &Progress,
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    key_column: usize,
+    options: &ConvertOptions,
+    report: &mut ExportReport,
+) -> Result<(), String> {
+    let note = |cleaned: bool| if cleaned { " (已自动去除)" } else { "" };
+    let mut checked = HashSet::new();
+    for sheet_config in sheet_configs {
+        if !checked.insert(&sheet_config.name) {
+            continue;
+        }
+        let Some(range) = ranges.get(&sheet_config.name) else {
+            continue;
+        };
+        // 表头行超出范围的错误在导出时报告
+        let Ok(Some(header_row)) = sheet_config.header(range) else {
+            continue;
+        };
+        let key_col = sheet_config.key_column.unwrap_or(key_column);
+        let key_type = sheet_config.column_type(header_row, key_col);
+        let mut invisible_rows = Vec::new();
+        let mut whitespace_rows = Vec::new();
+        let mut record = |issue: Issue, rows: &mut Vec<usize>| {
+            if options.verbose {
+                return report.record(progress, issue);
+            }
+            rows.extend(issue.row);
+            report.record_silent(issue);
+            Ok(())
+        };
+        for (row_idx, row) in range.rows().enumerate().skip(sheet_config.header_row + 1) {
+            let mut key = get_cell_string(row.get(key_col).unwrap_or(&DataType::Empty), key_type);
+            if let Some(chars) = find_invisible_chars(&key, true) {
+                let issue = Issue {
+                    category: IssueCategory::InvisibleChar,
+                    sheet: Some(sheet_config.name.clone()),
+                    row: Some(row_idx + 1),
+                    key: Some(key.clone()),
+                    message: format!(
+                        "Key 含有不可见字符 Sheet: '{}' 行: {} Key: {:?} 字符: {}{}",
+                        sheet_config.name,
+                        row_idx + 1,
+                        key,
+                        chars,
+                        note(options.clean_invisible)
+                    ),
+                    ..Default::default()
+                };
+                record(issue, &mut invisible_rows)?;
+                if options.clean_invisible {
+                    key = clean_invisible_chars(&key, true);
+                }
+            }
+            if key.trim() != key {
+                let issue = Issue {
+                    category: IssueCategory::KeyWhitespace,
+                    sheet: Some(sheet_config.name.clone()),
+                    row: Some(row_idx + 1),
+                    message: format!(
+                        "Key 首尾有空白 Sheet: '{}' 行: {} Key: {:?}{}",
+                        sheet_config.name,
+                        row_idx + 1,
+                        key,
+                        note(options.trim_keys)
+                    ),
+                    key: Some(key),
+                    ..Default::default()
+                };
+                record(issue, &mut whitespace_rows)?;
+            }
+        }
+        let summaries = [
+            (
+                "Key 含有不可见字符",
+                invisible_rows,
+                options.clean_invisible,
+            ),
+            ("Key 首尾有空白", whitespace_rows, options.trim_keys),
+        ];
+        for (label, rows, cleaned) in summaries {
+            if rows.is_empty() {
+                continue;
+            }
+            send_progress(
+                progress,
+                &format!(
+                    "{} Sheet: '{}' 共 {} 个 ({}){}",
+                    label,
+                    sheet_config.name,
+                    rows.len(),
+                    row_sample(&rows),
+                    note(cleaned)
+                ),
+                LogType::Warning,
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// 检查所有工作表的 key 命名。key 与语言无关，在导出各语言前统一检查一次；
+/// 复数后缀 `#one` 等不参与检查
+fn check_key_naming(
+    progress: &Progress,
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    key_column: usize,
+    options: &ConvertOptions,
+    naming: KeyNaming,
+    report: &mut ExportReport,
+) -> Result<(), ExportError> {
+    let mut checked = HashSet::new();
+    for sheet_config in sheet_configs {
+        if !checked.insert(&sheet_config.name) {
+            continue;
+        }
+        let Some(range) = ranges.get(&sheet_config.name) else {
+            continue;
+        };
+        let Some(header_row) = sheet_config.header(range)? else {
+            continue;
+        };
+        for (row_idx, _, key) in sheet_keys(range, sheet_config, header_row, key_column, options) {
+            let name = split_plural_key(&key).map_or(key.as_str(), |(base, _)| base);
+            if name.split('.').all(|segment| naming.matches(segment)) {
+                continue;
+            }
+            let err = ExportError::KeyNaming {
+                sheet: sheet_config.name.clone(),
+                row: row_idx + 1,
+                key: key.clone(),
+                convention: naming.name().to_string(),
+            };
+            if options.strict && !options.validate_only {
+                return Err(err);
+            }
+            report.record(
+                progress,
+                Issue {
+                    level: if options.strict {
+                        IssueLevel::Error
+                    } else {
+                        IssueLevel::Warning
+                    },
+                    category: IssueCategory::KeyNaming,
+                    sheet: Some(sheet_config.name.clone()),
+                    row: Some(row_idx + 1),
+                    key: Some(key.to_string()),
+                    message: err.to_string(),
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// 检查合并到同一个对象时会互相覆盖的 key: 不同 root 工作表中的同一个 key (展开时含父子关系)，
+/// 以及 root 工作表的 key 与其他工作表的 json 路径重叠。报告全部冲突，
+/// strict 开启时视为错误并中止导出 (校验模式下记录为错误)。同一工作表内的重复 key 另行报告
+fn check_key_collisions(
+    progress: &Progress,
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    key_column: usize,
+    options: &ConvertOptions,
+    nesting: OutputNesting,
+    report: &mut ExportReport,
+) -> Result<(), ExportError> {
+    // 顶层对象中的路径 -> 最先占用它的 (工作表序号, 行号)，非 root 工作表整体占用其 json 路径，没有行号
+    type Owner = (usize, Option<usize>);
+    let mut paths: HashMap<Vec<String>, Owner> = HashMap::new();
+    // 已占用路径的所有上级路径
+    let mut parents: HashMap<Vec<String>, (Owner, Vec<String>)> = HashMap::new();
+    let describe = |path: &[String], (sheet, row): Owner| {
+        let sheet = &sheet_configs[sheet].name;
+        match row {
+            Some(row) => format!("'{}' (工作表 {} 第 {} 行)", path.join("."), sheet, row),
+            None => format!("'{}' (工作表 {} 的 json 路径)", path.join("."), sheet),
+        }
+    };
+    let mut issues = Vec::new();
+    let mut claim = |path: Vec<String>, owner: Owner| {
+        let existing = (1..=path.len())
+            .find_map(|len| paths.get(&path[..len]).map(|o| (*o, path[..len].to_vec())))
+            .or_else(|| parents.get(&path).map(|(o, p)| (*o, p.clone())))
+            .filter(|(o, _)| o.0 != owner.0);
+        if let Some((other, other_path)) = existing {
+            issues.push(Issue {
+                level: if options.strict {
+                    IssueLevel::Error
+                } else {
+                    IssueLevel::Warning
+                },
+                category: IssueCategory::KeyCollision,
+                sheet: Some(sheet_configs[owner.0].name.clone()),
+                row: owner.1,
+                key: Some(path.join(".")),
+                message: format!(
+                    "Key 冲突 {} 与 {} 会在合并时互相覆盖",
+                    describe(&path, owner),
+                    describe(&other_path, other)
+                ),
+                ..Default::default()
+            });
+        }
+        for len in 1..path.len() {
+            parents
+                .entry(path[..len].to_vec())
+                .or_insert_with(|| (owner, path.clone()));
+        }
+        paths.entry(path).or_insert(owner);
+    };
+
+    let mut checked = HashSet::new();
+    for (index, sheet_config) in sheet_configs.iter().enumerate() {
+        if !checked.insert(&sheet_config.name) {
+            continue;
+        }
+        let owner = |row| (index, row);
+        if sheet_config.sheet_type.as_deref() != Some("root") {
+            claim(split_dotted_key(&sheet_config.json_path), owner(None));
+            continue;
+        }
+        let Some(range) = ranges.get(&sheet_config.name) else {
+            continue;
+        };
+        let Ok(Some(header_row)) = sheet_config.header(range) else {
+            continue;
+        };
+        let mut seen = HashSet::new();
+        for (row_idx, _, key) in sheet_keys(range, sheet_config, header_row, key_column, options) {
+            if !seen.insert(key.clone()) {
+                continue;
+            }
+            let path = if nesting == OutputNesting::Nested {
+                split_dotted_key(&key)
+            } else {
+                vec![key]
+            };
+            claim(path, owner(Some(row_idx + 1)));
+        }
+    }
+
+    if options.strict && !options.validate_only && !issues.is_empty() {
+        return Err(ExportError::KeyCollisions { issues });
+    }
+    for issue in issues {
+        report.record(progress, issue)?;
+    }
+    Ok(())
+}
+
+/// 列出各工作表中不对应任何配置语言的表头，通常是表头拼写错误 (如 `en-US` 写成 `en_US`)，
+/// 这些列不会被导出。key 列、说明列、状态列和 `#` 开头的元数据列不算在内
+fn check_unused_columns(
+    progress: &Progress,
+    sheet_configs: &[SheetConfig],
+    ranges: &HashMap<String, Range<DataType>>,
+    language_codes: &HashSet<String>,
+    key_column: usize,
+    options: &ConvertOptions,
+    report: &mut ExportReport,
+) -> Result<(), String> {
+    let known: Vec<&str> = [&options.context_column, &options.status_column]
+        .into_iter()
+        .filter_map(|c| c.as_deref().map(str::trim))
+        .filter(|c| !c.is_empty())
+        .collect();
+    let mut checked = HashSet::new();
+    for sheet_config in sheet_configs {
+        if !checked.insert(&sheet_config.name) {
+            continue;
+        }
+        let Some(range) = ranges.get(&sheet_config.name) else {
+            continue;
+        };
+        // 表头行超出范围的错误在导出时报告
+        let Ok(Some(header_row)) = sheet_config.header(range) else {
+            continue;
+        };
+        let key_col = sheet_config.key_column.unwrap_or(key_column);
+        let unused: Vec<String> = header_row
+            .iter()
+            .enumerate()
+            .filter(|(col, _)| *col != key_col)
+            .map(|(_, c)| get_cell_string(c, ColumnType::Auto))
+            .filter(|h| !h.trim().is_empty() && !is_metadata_header(h))
+            .filter(|h| !known.contains(&h.trim()) && !language_codes.contains(h))
+            .map(|h| format!("'{}'", h))
+            .collect();
+        if unused.is_empty() {
+            continue;
+        }
+        report.record(
+            progress,
+            Issue {
+                category: IssueCategory::UnknownColumn,
+                sheet: Some(sheet_config.name.clone()),
+                message: format!(
+                    "工作表 {} 中有 {} 列不对应任何配置的语言, 不会被导出: {}",
+                    sheet_config.name,
+                    unused.len(),
+                    unused.join(", ")
+                ),
+                ..Default::default()
+            },
+        )?;
+    }
+    Ok(())
+}
+
+/// 术语引用 `{{@term}}`，与运行时占位符 `{{name}}` 区分开
+static GLOSSARY_REF_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"\{\{@([^{}]*)\}\}").unwrap());
+
+/// 读取术语表中某个语言的所有术语，术语表没有该语言列时返回空表。
+/// 表头行和 key 列按术语表的 sheet 配置读取
+fn read_glossary(
+    range: &Range<DataType>,
+    sheet_config: &SheetConfig,
+    code: &str,
+    key_column: usize,
+    options: &ConvertOptions,
+) -> Result<IndexMap<String, String>, String> {
+    let mut terms = IndexMap::new();
+    let Some(header_row) = sheet_config.header(range)? else {
+        return Ok(terms);
+    };
+    let Some(lang_col) = find_language_column(header_row, code) else {
+        return Ok(terms);
+    };
+    for (_, row, term) in sheet_keys(range, sheet_config, header_row, key_column, options) {
+        let value = row
+            .get(lang_col)
+            .map(|c| normalize_value(get_cell_string(c, ColumnType::Auto), options))
+            .unwrap_or_default();
+        if !term.trim().is_empty() && !value.is_empty() {
+            terms.insert(term.trim().to_string(), value);
+        }
+    }
+    Ok(terms)
+}
+
+/// 替换值中的术语引用，术语的值中也可以引用其他术语。
+/// `stack` 为正在展开的术语链，用于发现循环引用
+fn resolve_glossary(
+    value: &str,
+    glossary: &IndexMap<String, String>,
+    stack: &mut Vec<String>,
+) -> Result<String, String> {
+    let mut out = String::with_capacity(value.len());
+    let mut last = 0;
+    for caps in GLOSSARY_REF_RE.captures_iter(value) {
+        let whole = caps.get(0).unwrap();
+        let term = caps[1].trim();
+        if stack.iter().any(|t| t == term) {
+            return Err(format!("术语循环引用: {} -> {}", stack.join(" -> "), term));
+        }
+        let Some(term_value) = glossary.get(term) else {
+            return Err(format!("未定义的术语: {}", term));
+        };
+        stack.push(term.to_string());
+        let resolved = resolve_glossary(term_value, glossary, stack)?;
+        stack.pop();
+
+        out.push_str(&value[last..whole.start()]);
+        out.push_str(&resolved);
+        last = whole.end();
+    }
+    out.push_str(&value[last..]);
+    Ok(out)
+}
+
+/// 低内存模式下代替原值保留: 只包含占位符，空值仍为空，其余非空值为一个空格。
+/// 跨语言的占位符、缺失 key 和完成度检查只依赖这些信息
+fn placeholder_skeleton(value: &str, syntax: &PlaceholderSyntax) -> String {
+    if value.is_empty() {
+        return String::new();
+    }
+    let skeleton: String = extract_placeholder_names(value, syntax)
+        .iter()
+        .map(|name| syntax.wrap(name))
+        .collect();
+    if skeleton.is_empty() {
+        " ".to_string()
+    } else {
+        skeleton
+    }
+}
+
+/// 提取值中所有占位符 (默认 `{{name}}`) 的名称
+fn extract_placeholder_names(value: &str, syntax: &PlaceholderSyntax) -> BTreeSet<String> {
+    syntax
+        .name_re
+        .captures_iter(value)
+        .map(|c| c[1].to_string())
+        .collect()
+}
+
+/// 跨语言比较同一个 key 的占位符名称，返回所有不一致的问题
+fn find_placeholder_mismatches(
+    lang_data: &[(String, SheetDataMap)],
+    syntax: &PlaceholderSyntax,
+) -> Vec<Issue> {
+    // 按 sheet/key 首次出现的顺序遍历，保证输出稳定
+    let mut keys: IndexSet<(&str, &str)> = IndexSet::new();
+    for (_, data) in lang_data {
+        for (sheet, temp) in data {
+            for key in temp.keys() {
+                keys.insert((sheet.as_str(), key.as_str()));
+            }
+        }
+    }
+
+    let mut mismatches = Vec::new();
+    for (sheet, key) in &keys {
+        // 空值已经有单独的警告，保留类型的布尔和数字没有占位符，都不参与比较
+        let sets: Vec<(&str, BTreeSet<String>)> = lang_data
+            .iter()
+            .filter_map(|(code, data)| {
+                let CellValue::Text(value) = data.get(*sheet)?.get(*key)? else {
+                    return None;
+                };
+                if value.is_empty() {
+                    return None;
+                }
+                Some((code.as_str(), extract_placeholder_names(value, syntax)))
+            })
+            .collect();
+
+        if sets.windows(2).all(|w| w[0].1 == w[1].1) {
+            continue;
+        }
+
+        let detail = sets
+            .iter()
+            .map(|(code, names)| {
+                let names: Vec<&str> = names.iter().map(|n| n.as_str()).collect();
+                format!("{}: {{{}}}", code, names.join(", "))
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
+        mismatches.push(Issue {
+            category: IssueCategory::PlaceholderMismatch,
+            sheet: Some(sheet.to_string()),
+            key: Some(key.to_string()),
+            message: format!("占位符不一致 Sheet: '{}' Key: '{}' {}", sheet, key, detail),
+            ..Default::default()
+        });
+    }
+
+    mismatches
+}
+
+/// 统计各语言的 key 数量和完成度，空值一律算作未完成。
+/// 指定了基准语言时完成度为基准语言的 key 中有值的比例；
+/// 否则以 key 最多的语言的 key 数量为分母
+fn language_coverage(
+    lang_data: &[(String, SheetDataMap)],
+    base_language: Option<&str>,
+    fallback_counts: &HashMap<String, usize>,
+) -> Vec<LanguageCoverage> {
+    let base = base_language.and_then(|b| lang_data.iter().find(|(code, _)| code == b));
+    let key_count = |data: &SheetDataMap| data.values().map(|temp| temp.len()).sum::<usize>();
+    let max_keys = lang_data
+        .iter()
+        .map(|(_, data)| key_count(data))
+        .max()
+        .unwrap_or_default();
+
+    lang_data
+        .iter()
+        .map(|(code, data)| {
+            let total_keys = key_count(data);
+            // 回退的值来自基准语言，不算作已翻译
+            let fallback_keys = fallback_counts.get(code).copied().unwrap_or_default();
+            let non_empty_keys = data
+                .values()
+                .flat_map(|temp| temp.values())
+                .filter(|v| !v.is_empty())
+                .count()
+                .saturating_sub(fallback_keys);
+            let (covered, reference) = match base {
+                Some((_, base)) => {
+                    let base_keys = key_count(base);
+                    let missing = find_missing_keys(base, data).len();
+                    (
+                        (base_keys - missing).saturating_sub(fallback_keys),
+                        base_keys,
+                    )
+                }
+                None => (non_empty_keys, max_keys),
+            };
+            LanguageCoverage {
+                lang: code.clone(),
+                total_keys,
+                non_empty_keys,
+                fallback_keys,
+                coverage_pct: if reference == 0 {
+                    100.0
+                } else {
+                    (covered as f64 * 10000.0 / reference as f64).round() / 100.0
+                },
+            }
+        })
+        .collect()
+}
+
+/// 找出基准语言中存在、但目标语言缺失或为空的 (sheet, key)
+fn find_missing_keys<'a>(base: &'a SheetDataMap, target: &SheetDataMap) -> Vec<(&'a str, &'a str)> {
+    let mut missing = Vec::new();
+    for (sheet, temp) in base {
+        for key in temp.keys() {
+            let present = target
+                .get(sheet)
+                .and_then(|t| t.get(key))
+                .is_some_and(|v| !v.is_empty());
+            if !present {
+                missing.push((sheet.as_str(), key.as_str()));
+            }
+        }
+    }
+    missing
+}
+
+/// 合并 required_keys 和 required_keys_file 中的必需 key，保持首次出现的顺序
+fn load_required_keys(options: &ConvertOptions) -> Result<IndexSet<String>, String> {
+    let mut keys: IndexSet<String> = options
+        .required_keys
+        .iter()
+        .flatten()
+        .map(|k| k.trim().to_string())
+        .filter(|k| !k.is_empty())
+        .collect();
+    if let Some(path) = options.required_keys_file.as_deref().map(str::trim) {
+        if !path.is_empty() {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("读取必需 key 列表失败: {}: {}", path, e))?;
+            keys.extend(
+                content
+                    .lines()
+                    .map(str::trim)
+                    .filter(|l| !l.is_empty() && !l.starts_with('#'))
+                    .map(str::to_string),
+            );
+        }
+    }
+    Ok(keys)
+}
+
+/// 找出某个语言缺失或为空的必需 key
+fn find_missing_required_keys<'a>(
+    required: &'a IndexSet<String>,
+    sheet_configs: &[SheetConfig],
+    data: &SheetDataMap,
+) -> Vec<&'a str> {
+    let mut present = HashSet::new();
+    for sheet_config in sheet_configs {
+        let Some(temp) = data.get(&sheet_config.name) else {
+            continue;
+        };
+        let prefix = sheet_config.key_prefix();
+        for (key, _) in temp.iter().filter(|(_, v)| !v.is_empty()) {
+            present.insert(format!("{}{}", prefix, key.replace("\\.", ".")));
+        }
+    }
+    required
+        .iter()
+        .filter(|k| !present.contains(k.as_str()))
+        .map(|k| k.as_str())
+        .collect()
+}
+
+/// CLDR 复数类别
+const PLURAL_CATEGORIES: [&str; 6] = ["zero", "one", "two", "few", "many", "other"];
+
+/// 拆分复数 key: `item#one` -> (`item`, `one`)，不是复数 key 时返回 None
+fn split_plural_key(key: &str) -> Option<(&str, &str)> {
+    let (base, category) = key.rsplit_once('#')?;
+    (!base.is_empty() && PLURAL_CATEGORIES.contains(&category)).then_some((base, category))
+}
+
+/// 语言必须提供的复数类别 (按 CLDR 规则，只看主语言代码)；
+/// `zero` 等可选类别不要求，未收录的语言只要求 `other`
+fn required_plural_categories(code: &str) -> &'static [&'static str] {
+    let lang = code
+        .split(['-', '_'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase();
+    match lang.as_str() {
+        "zh" | "ja" | "ko" | "vi" | "th" | "id" | "ms" => &["other"],
+        "en" | "de" | "nl" | "sv" | "da" | "no" | "nb" | "fi" | "el" | "hu" | "tr" | "bg"
+        | "et" => &["one", "other"],
+        "fr" | "es" | "it" | "pt" | "ca" => &["one", "many", "other"],
+        "ru" | "uk" | "pl" | "cs" | "sk" | "lt" => &["one", "few", "many", "other"],
+        "hr" | "sr" | "bs" | "ro" => &["one", "few", "other"],
+        "he" => &["one", "two", "other"],
+        "sl" => &["one", "two", "few", "other"],
+        "ar" | "cy" => &["zero", "one", "two", "few", "many", "other"],
+        _ => &["other"],
+    }
+}
+
+/// 递归地将对象中的复数 key 归组，`groups` 收集每组的点分基础 key -> 类别 -> 值
+fn group_plurals(
+    value: &JsonValue,
+    format: PluralFormat,
+    prefix: &str,
+    groups: &mut IndexMap<String, IndexMap<String, String>>,
+) -> JsonValue {
+    let JsonValue::Object(obj) = value else {
+        return value.clone();
+    };
+    let mut grouped = JsonValue::new_object();
+    for (k, v) in obj.iter() {
+        let path = |key: &str| {
+            if prefix.is_empty() {
+                key.to_string()
+            } else {
+                format!("{}.{}", prefix, key)
+            }
+        };
+        match split_plural_key(k).filter(|_| !v.is_object()) {
+            Some((base, category)) => {
+                groups.entry(path(base)).or_default().insert(
+                    category.to_string(),
+                    v.as_str().map_or_else(|| v.dump(), str::to_string),
+                );
+                match format {
+                    PluralFormat::I18next => grouped[format!("{}_{}", base, category)] = v.clone(),
+                    PluralFormat::Nested => {
+                        if !grouped[base].is_object() {
+                            grouped[base] = JsonValue::new_object();
+                        }
+                        grouped[base][category] = v.clone();
+                    }
+                }
+            }
+            None => grouped[k] = group_plurals(v, format, &path(k), groups),
+        }
+    }
+    grouped
+}
+
+/// 按选项对单个输出做最后的处理: 展开为单层点分 key、排序
+fn finish_output(mut value: JsonValue, nesting: OutputNesting, sort_keys: bool) -> JsonValue {
+    if nesting == OutputNesting::Flat {
+        let mut flat = IndexMap::new();
+        flatten_json(&value, "", &mut flat);
+        value = JsonValue::new_object();
+        for (k, v) in flat {
+            value[k.replace("\\.", ".")] = v.into();
+        }
+    }
+    if sort_keys {
+        value = sort_json_keys(&value);
+    }
+    value
+}
+
+/// 递归地按 key 排序对象。比较时忽略大小写，大小写不同的相同 key 再按原文排序，保证结果确定
+fn sort_json_keys(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(obj) => {
+            let mut entries: Vec<(&str, &JsonValue)> = obj.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| {
+                a.to_lowercase()
+                    .cmp(&b.to_lowercase())
+                    .then_with(|| a.cmp(b))
+            });
+            let mut sorted = JsonValue::new_object();
+            for (k, v) in entries {
+                sorted[k] = sort_json_keys(v);
+            }
+            sorted
+        }
+        JsonValue::Array(arr) => JsonValue::Array(arr.iter().map(sort_json_keys).collect()),
+        other => other.clone(),
+    }
+}
+
+static EMPTY_FLAT: LazyLock<IndexMap<String, String>> = LazyLock::new(IndexMap::new);
+
+/// 删除与快照中值相同的叶子节点和因此变空的对象，返回保留的叶子数量。
+/// `prefix` 为 value 在完整结果中的点分路径，路径规则与 flatten_json 相同
+fn retain_changed(
+    value: &mut JsonValue,
+    prefix: &str,
+    snapshot: &IndexMap<String, String>,
+) -> usize {
+    let JsonValue::Object(obj) = value else {
+        return 0;
+    };
+    let keys: Vec<String> = obj.iter().map(|(k, _)| k.to_string()).collect();
+    let mut kept = 0;
+    for key in keys {
+        let segment = key.replace('.', "\\.");
+        let path = if prefix.is_empty() {
+            segment
+        } else {
+            format!("{}.{}", prefix, segment)
+        };
+        let child = &mut obj[key.as_str()];
+        let keep = if child.is_object() {
+            let n = retain_changed(child, &path, snapshot);
+            kept += n;
+            n > 0
+        } else {
+            let mut flat = IndexMap::new();
+            flatten_json(child, &path, &mut flat);
+            let changed = snapshot.get(&path) != flat.get(&path);
+            kept += changed as usize;
+            changed
+        };
+        if !keep {
+            obj.remove(&key);
+        }
+    }
+    kept
+}
+
+/// 将嵌套对象展开为带点的 key，key 中原有的 `.` 会被转义为 `\.`
+fn flatten_json(value: &JsonValue, prefix: &str, out: &mut IndexMap<String, String>) {
+    match value {
+        JsonValue::Object(obj) => {
+            for (k, v) in obj.iter() {
+                let segment = k.replace('.', "\\.");
+                let path = if prefix.is_empty() {
+                    segment
+                } else {
+                    format!("{}.{}", prefix, segment)
+                };
+                flatten_json(v, &path, out);
+            }
+        }
+        JsonValue::String(_) | JsonValue::Short(_) => {
+            out.insert(
+                prefix.to_string(),
+                value.as_str().unwrap_or_default().to_string(),
+            );
+        }
+        JsonValue::Null => {
+            out.insert(prefix.to_string(), String::new());
+        }
+        other => {
+            out.insert(prefix.to_string(), other.dump());
+        }
+    }
+}
+
+// 配置结构体
+#[derive(Debug, Clone)]
+struct SheetConfig {
+    name: String,
+    /// "root" / "nested"，不填为普通工作表；"ignore" 的配置行在读取时就被跳过
+    sheet_type: Option<String>,
+    /// 列类型声明，key 为表头名称
+    column_types: HashMap<String, ColumnType>,
+    /// key 所在列 (从 0 开始)，未配置时使用全局设置或第一列
+    key_column: Option<usize>,
+    /// 表头所在行 (从 0 开始)，之前的标题、说明等行不读取，默认第一行
+    header_row: usize,
+    /// 非 root 工作表在输出中的父级路径，点分格式 (key 中的点用 `\.` 转义)，
+    /// 默认为工作表名称本身
+    json_path: String,
+    /// 值转换操作，按配置顺序排列；语言为 None 的对所有语言列生效
+    transforms: Vec<(Option<String>, TransformOp)>,
+}
+
+/// 一个值转换操作
+#[derive(Debug, Clone, PartialEq)]
+enum TransformOp {
+    Uppercase,
+    Lowercase,
+    Trim,
+    /// `replace:from:to`，from 中不能有冒号，to 可以为空
+    Replace(String, String),
+}
+
+impl TransformOp {
+    fn parse(spec: &str) -> Option<Self> {
+        let spec = spec.trim_start();
+        if let Some(args) = spec.strip_prefix("replace:") {
+            let (from, to) = args.split_once(':')?;
+            return (!from.is_empty()).then(|| TransformOp::Replace(from.into(), to.into()));
+        }
+        match spec.trim_end().to_lowercase().as_str() {
+            "uppercase" => Some(TransformOp::Uppercase),
+            "lowercase" => Some(TransformOp::Lowercase),
+            "trim" => Some(TransformOp::Trim),
+            _ => None,
+        }
+    }
+}
+
+/// 一个语言列上依次执行的转换。大小写和替换只作用于占位符之外的文本，
+/// 占位符名称保持原样；trim 作用于整个值
+struct TransformPipeline<'a> {
+    ops: Vec<&'a TransformOp>,
+}
+
+impl TransformPipeline<'_> {
+    fn is_empty(&self) -> bool {
+        self.ops.is_empty()
+    }
+
+    fn apply(&self, value: &str, syntax: &PlaceholderSyntax) -> String {
+        let mut value = value.to_string();
+        for op in &self.ops {
+            value = match op {
+                TransformOp::Trim => value.trim().to_string(),
+                op => map_outside_placeholders(&value, syntax, |text| match op {
+                    TransformOp::Uppercase => text.to_uppercase(),
+                    TransformOp::Lowercase => text.to_lowercase(),
+                    TransformOp::Replace(from, to) => text.replace(from.as_str(), to),
+                    TransformOp::Trim => text.to_string(),
+                }),
+            };
+        }
+        value
+    }
+}
+
+/// 对占位符之间的文本应用 `f`，占位符本身原样保留
+fn map_outside_placeholders(
+    value: &str,
+    syntax: &PlaceholderSyntax,
+    f: impl Fn(&str) -> String,
+) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut last = 0;
+    for m in syntax.name_re.find_iter(value) {
+        out.push_str(&f(&value[last..m.start()]));
+        out.push_str(m.as_str());
+        last = m.end();
+    }
+    out.push_str(&f(&value[last..]));
+    out
+}
+
+/// 解析列位置: 列字母 ("A"、"B"、"AA"，不区分大小写) 或从 1 开始的列号，返回从 0 开始的索引
+fn parse_column_index(value: &str) -> Option<usize> {
+    let value = value.trim();
+    if let Ok(n) = value.parse::<usize>() {
+        return n.checked_sub(1);
+    }
+    if value.is_empty() || !value.chars().all(|c| c.is_ascii_alphabetic()) {
+        return None;
+    }
+    value
+        .to_ascii_uppercase()
+        .bytes()
+        .try_fold(0usize, |acc, b| {
+            acc.checked_mul(26)?.checked_add((b - b'A' + 1) as usize)
+        })
+        .map(|n| n - 1)
+}
+
+impl SheetConfig {
+    /// 没有任何配置的普通工作表
+    fn plain(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            sheet_type: None,
+            column_types: HashMap::new(),
+            key_column: None,
+            header_row: 0,
+            json_path: name.to_string(),
+            transforms: Vec::new(),
+        }
+    }
+
+    /// 某个语言列上生效的转换: 整个工作表的和该语言的，按配置顺序
+    fn transforms_for(&self, code: &str) -> TransformPipeline<'_> {
+        TransformPipeline {
+            ops: self
+                .transforms
+                .iter()
+                .filter(|(lang, _)| lang.as_deref().is_none_or(|l| l == code))
+                .map(|(_, op)| op)
+                .collect(),
+        }
+    }
+
+    /// 根据表头查找某一列声明的类型，未声明时返回 Auto
+    fn column_type(&self, header_row: &[DataType], col: usize) -> ColumnType {
+        header_row
+            .get(col)
+            .and_then(|c| self.column_types.get(&get_cell_string(c, ColumnType::Auto)))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// 取表头行，空工作表返回 None；配置的表头行超出工作表行数时报错。
+    /// 数据从表头的下一行开始，行号仍按整个工作表计算
+    fn header<'r>(&self, range: &'r Range<DataType>) -> Result<Option<&'r [DataType]>, String> {
+        if range.is_empty() {
+            return Ok(None);
+        }
+        range.rows().nth(self.header_row).map(Some).ok_or_else(|| {
+            format!(
+                "工作表 {} 的表头行 (第 {} 行) 超出范围, 该表只有 {} 行",
+                self.name,
+                self.header_row + 1,
+                range.height()
+            )
+        })
+    }
+
+    /// 该工作表的 key 在合并结果中的前缀，如 `ui.menu.`；root 工作表没有前缀
+    fn key_prefix(&self) -> String {
+        if self.sheet_type.as_deref() == Some("root") {
+            String::new()
+        } else {
+            format!("{}.", self.json_path.replace("\\.", "."))
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct LanguageConfig {
+    code: String,
+    /// 语言配置第二列标记为 rtl 的从右到左语言 (阿拉伯语、希伯来语等)
+    rtl: bool,
+    /// 语言配置第三列的回退语言，如 `en-GB` 回退到 `en`，空值沿回退链向上取值
+    fallback: Option<String>,
+    /// 语言配置第四列，local_date 列使用的 chrono 日期格式，如 `%d/%m/%Y`
+    date_format: Option<String>,
+    /// 语言配置第五列，local_number 列使用的数字格式
+    number_format: Option<NumberFormat>,
+}
+
+/// 本地化数字的小数点和千位分隔符
+#[derive(Debug, Clone, Copy)]
+struct NumberFormat {
+    decimal: char,
+    group: Option<char>,
+}
+
+impl NumberFormat {
+    /// 按示例数字 1234.5 的写法解析，如 `1,234.5`、`1.234,5`、`1 234,5`、`1234,5`
+    fn parse(sample: &str) -> Option<Self> {
+        let chars: Vec<char> = sample.trim().chars().collect();
+        match chars.as_slice() {
+            ['1', group, '2', '3', '4', decimal, '5']
+                if !group.is_ascii_digit() && !decimal.is_ascii_digit() && group != decimal =>
+            {
+                Some(Self {
+                    decimal: *decimal,
+                    group: Some(*group),
+                })
+            }
+            ['1', '2', '3', '4', decimal, '5'] if !decimal.is_ascii_digit() => Some(Self {
+                decimal: *decimal,
+                group: None,
+            }),
+            _ => None,
+        }
+    }
+
+    fn format(self, f: f64) -> String {
+        let text = format_number(f.abs());
+        let (int_part, fraction) = match text.split_once('.') {
+            Some((i, frac)) => (i, Some(frac)),
+            None => (text.as_str(), None),
+        };
+        let mut formatted = String::new();
+        if f < 0.0 {
+            formatted.push('-');
+        }
+        for (i, digit) in int_part.chars().enumerate() {
+            if let Some(group) = self.group {
+                if i > 0 && (int_part.len() - i) % 3 == 0 {
+                    formatted.push(group);
+                }
+            }
+            formatted.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            formatted.push(self.decimal);
+            formatted.push_str(fraction);
+        }
+        formatted
+    }
+}
+
+/// 根据扩展名打开对应类型的工作簿 (xlsx / xlsm / xlsb / xls / ods)
+fn open_workbook_by_extension(
+    path: &Path,
+    password: Option<&str>,
+) -> Result<Sheets<WorkbookSource>, ExportError> {
+    let (ext, source) = open_workbook_source(path, password)?;
+    let workbook = match ext.as_str() {
+        "xlsx" | "xlsm" => Xlsx::new(source)
+            .map(Sheets::Xlsx)
+            .map_err(|e| e.to_string()),
+        "xlsb" => Xlsb::new(source)
+            .map(Sheets::Xlsb)
+            .map_err(|e| e.to_string()),
+        "xls" => Xls::new(source).map(Sheets::Xls).map_err(|e| e.to_string()),
+        _ => Ods::new(source).map(Sheets::Ods).map_err(|e| e.to_string()),
+    };
+    Ok(workbook.map_err(|e| format!("打开文件失败: {}", e))?)
+}
+
+/// 检查扩展名并打开工作簿文件，加密的工作簿先解密，返回小写的扩展名和文件内容
+fn open_workbook_source(
+    path: &Path,
+    password: Option<&str>,
+) -> Result<(String, WorkbookSource), ExportError> {
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !matches!(ext.as_str(), "xlsx" | "xlsm" | "xlsb" | "xls" | "ods") {
+        return Err(format!(
+            "不支持的文件类型: .{} (支持 xlsx / xlsm / xlsb / xls / ods)",
+            ext
+        )
+        .into());
+    }
+    let encryption_error = |problem, detail: Option<&str>| ExportError::Encryption {
+        path: path.display().to_string(),
+        problem,
+        detail: detail.map(str::to_string),
+    };
+    let open_err = |e: std::io::Error| format!("打开文件失败: {}", e);
+    let mut file = File::open(path).map_err(open_err)?;
+    // 加密的 xlsx / xlsb 不是 zip，而是包含加密数据的 OLE 复合文档
+    let mut signature = [0u8; 8];
+    let is_cfb = matches!(ext.as_str(), "xlsx" | "xlsm" | "xlsb")
+        && file.read_exact(&mut signature).is_ok()
+        && signature == CFB_SIGNATURE;
+    file.rewind().map_err(open_err)?;
+    let source = match (is_cfb, password.filter(|p| !p.is_empty())) {
+        (true, None) => return Err(encryption_error(EncryptionProblem::PasswordRequired, None)),
+        (true, Some(password)) => match decrypt_workbook(file, password) {
+            Ok(data) => WorkbookSource::Decrypted(Cursor::new(data)),
+            Err((problem, detail)) => return Err(encryption_error(problem, detail.as_deref())),
+        },
+        (false, Some(_)) if matches!(ext.as_str(), "xls" | "ods") => {
+            return Err(encryption_error(
+                EncryptionProblem::Unsupported,
+                Some("只支持解密 xlsx / xlsm / xlsb"),
+            ))
+        }
+        (false, Some(_)) => return Err(encryption_error(EncryptionProblem::NotEncrypted, None)),
+        (false, None) => WorkbookSource::File(BufReader::new(file)),
+    };
+    Ok((ext, source))
+}
+
+/// 工作簿的数据来源: 直接读取文件，或解密后放在内存中的内容
+enum WorkbookSource {
+    File(BufReader<File>),
+    Decrypted(Cursor<Vec<u8>>),
+}
+
+impl Read for WorkbookSource {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            WorkbookSource::File(r) => r.read(buf),
+            WorkbookSource::Decrypted(r) => r.read(buf),
+        }
+    }
+}
+
+impl Seek for WorkbookSource {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            WorkbookSource::File(r) => r.seek(pos),
+            WorkbookSource::Decrypted(r) => r.seek(pos),
+        }
+    }
+}
+
+/// 加密工作簿无法打开的原因，前端据此提示输入密码或更换文件
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncryptionProblem {
+    PasswordRequired,
+    WrongPassword,
+    NotEncrypted,
+    Unsupported,
+}
+
+/// OLE 复合文档的文件头
+const CFB_SIGNATURE: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
+
+/// zip 文件头，xlsx / xlsm / xlsb 解密后应当以此开头
+const ZIP_SIGNATURE: [u8; 4] = *b"PK\x03\x04";
+
+/// 按 ECMA-376 解密 Office 加密文档 (MS-OFFCRYPTO)，返回原始的 xlsx / xlsb 内容。
+/// office_crypto 不校验密码，密码错误时只会得到无法读取的内容，因此以解密结果是否为 zip 判断
+fn decrypt_workbook(
+    mut file: File,
+    password: &str,
+) -> Result<Vec<u8>, (EncryptionProblem, Option<String>)> {
+    let mut raw = Vec::new();
+    file.read_to_end(&mut raw)
+        .map_err(|e| (EncryptionProblem::Unsupported, Some(e.to_string())))?;
+    match office_crypto::decrypt_from_bytes(raw, password) {
+        Ok(data) if data.starts_with(&ZIP_SIGNATURE) => Ok(data),
+        Ok(_) => Err((EncryptionProblem::WrongPassword, None)),
+        Err(office_crypto::DecryptError::Unimplemented(what)) => {
+            Err((EncryptionProblem::Unsupported, Some(what)))
+        }
+        Err(e) => Err((EncryptionProblem::Unsupported, Some(e.to_string()))),
+    }
+}
+
+/// XML 中所有名为 `name` 的元素 (忽略命名空间前缀) 的属性，属性值未反转义
+fn xml_elements_attrs<'x>(xml: &'x str, name: &str) -> Vec<HashMap<&'x str, &'x str>> {
+    static ELEMENT_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"<(?:\w+:)?(\w+)\b([^>]*)>").unwrap());
+    static ATTR_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"([\w:]+)\s*=\s*"([^"]*)""#).unwrap());
+    ELEMENT_RE
+        .captures_iter(xml)
+        .filter(|c| &c[1] == name)
+        .map(|c| {
+            ATTR_RE
+                .captures_iter(c.get(2).unwrap().as_str())
+                .map(|c| (c.get(1).unwrap().as_str(), c.get(2).unwrap().as_str()))
+                .collect()
+        })
+        .collect()
+}
+
+/// 还原 XML 文本中的实体 (`&amp;`、`&#10;` 等)，无法识别的实体保留原样
+fn unescape_xml(text: &str) -> String {
+    static ENTITY_RE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"&(#x[0-9a-fA-F]+|#[0-9]+|amp|lt|gt|quot|apos);").unwrap());
+    ENTITY_RE
+        .replace_all(text, |c: &regex::Captures| {
+            let entity = &c[1];
+            let code = match entity.strip_prefix("#x") {
+                Some(hex) => u32::from_str_radix(hex, 16).ok(),
+                None => entity.strip_prefix('#').and_then(|d| d.parse().ok()),
+            };
+            match (entity, code.and_then(char::from_u32)) {
+                (_, Some(ch)) => ch.to_string(),
+                ("amp", _) => "&".to_string(),
+                ("lt", _) => "<".to_string(),
+                ("gt", _) => ">".to_string(),
+                ("quot", _) => "\"".to_string(),
+                ("apos", _) => "'".to_string(),
+                _ => c[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+const DEFAULT_LANG_CONFIG_SHEET: &str = "导出语言管理";
+const DEFAULT_SHEET_CONFIG_SHEET: &str = "导出sheet管理";
+
+/// 读取配置工作表，找不到时在错误信息中列出工作簿里现有的工作表
+fn read_config_sheet<RS, R>(
+    workbook: &mut R,
+    sheet_name: &str,
+) -> Result<Range<DataType>, ExportError>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+    R::Error: std::fmt::Display,
+{
+    match workbook.worksheet_range(sheet_name) {
+        Some(range) => {
+            range.map_err(|e| ExportError::Other(format!("读取工作表 {} 失败: {}", sheet_name, e)))
+        }
+        None => Err(ExportError::SheetNotFound {
+            sheet: sheet_name.to_string(),
+            available: workbook.sheet_names().to_vec(),
+        }),
+    }
+}
+
+/// 一条语言配置，来自语言配置表的一行或外部配置文件
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct LanguageConfigEntry {
+    code: String,
+    rtl: bool,
+    fallback: Option<String>,
+    date_format: Option<String>,
+    number_format: Option<String>,
+}
+
+/// 外部配置文件，字段与配置表的各列对应，例如 TOML:
+///
+/// ```toml
+/// [[languages]]
+/// code = "en-GB"
+/// fallback = "en"
+///
+/// [[sheets]]
+/// name = "menu"
+/// type = "nested"
+/// key_column = "B"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Config {
+    languages: Option<Vec<LanguageConfigEntry>>,
+    sheets: Option<Vec<SheetConfigEntry>>,
+}
+
+/// 按扩展名读取 JSON 或 TOML 格式的外部配置文件
+fn load_config_file(path: &Path) -> Result<Config, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("读取配置文件 {} 失败: {}", path.display(), e))?;
+    let ext = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    match ext.as_str() {
+        "json" => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string()),
+        other => {
+            return Err(format!(
+                "不支持的配置文件格式: '{}' (支持 json / toml)",
+                other
+            ))
+        }
+    }
+    .map_err(|e| format!("解析配置文件 {} 失败: {}", path.display(), e))
+}
+
+/// 试着格式化一个不带时区的日期时间: 无效的格式符和 %z / %Z 等时区格式都会失败
+fn is_valid_date_format(format: &str) -> bool {
+    use std::fmt::Write as _;
+    let sample = NaiveDate::from_ymd_opt(2000, 1, 1).and_then(|d| d.and_hms_opt(0, 0, 0));
+    sample.is_some_and(|s| write!(String::new(), "{}", s.format(format)).is_ok())
+}
+
+/// 校验并转换语言配置，空字符串视为未填
+fn parse_language_config(entry: LanguageConfigEntry) -> Result<LanguageConfig, ExportError> {
+    let field = |v: Option<String>| v.map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+    let code = entry.code;
+    let date_format = field(entry.date_format);
+    if let Some(format) = &date_format {
+        if !is_valid_date_format(format) {
+            return Err(format!(
+                "语言 {} 的日期格式无效: '{}' (不支持 %z / %Z 等时区格式)",
+                code, format
+            )
+            .into());
+        }
+    }
+    let number_format = match field(entry.number_format) {
+        Some(sample) => Some(NumberFormat::parse(&sample).ok_or_else(|| {
+            format!(
+                "语言 {} 的数字格式无效: '{}' (应为 1234.5 的写法, 如 1,234.5)",
+                code, sample
+            )
+        })?),
+        None => None,
+    };
+    Ok(LanguageConfig {
+        code,
+        rtl: entry.rtl,
+        fallback: field(entry.fallback),
+        date_format,
+        number_format,
+    })
+}
+
+// 从 Excel 读取语言配置: 语言代码、rtl、回退语言、日期格式、数字格式依次排列
+fn read_language_configs_from_excel<RS, R>(
+    workbook: &mut R,
+    sheet_name: &str,
+) -> Result<Vec<LanguageConfig>, ExportError>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+    R::Error: std::fmt::Display,
+{
+    let range = read_config_sheet(workbook, sheet_name)?;
+
+    let mut configs = Vec::new();
+
+    for row in range.rows() {
+        if row.is_empty() {
+            continue;
+        }
+        let column = |col: usize| row.get(col).map(|c| get_cell_string(c, ColumnType::Auto));
+        let code = column(0).unwrap_or_default();
+        if code.is_empty() {
+            continue;
+        }
+        let rtl = column(1).is_some_and(|c| {
+            matches!(
+                c.trim().to_lowercase().as_str(),
+                "rtl" | "true" | "1" | "yes" | "是"
+            )
+        });
+        configs.push(parse_language_config(LanguageConfigEntry {
+            code,
+            rtl,
+            fallback: column(2),
+            date_format: column(3),
+            number_format: column(4),
+        })?);
+    }
+
+    Ok(configs)
+}
+
+/// 从各工作表的表头推断语言，按首次出现的顺序，推断出的语言都不使用 rtl、回退和本地格式。
+/// 工作表读取失败时跳过，错误在之后读取工作表内容时报告
+fn infer_language_configs<RS, R>(
+    workbook: &mut R,
+    sheet_configs: &[SheetConfig],
+    key_column: usize,
+    options: &ConvertOptions,
+) -> Result<Vec<LanguageConfig>, ExportError>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+{
+    let known: Vec<&str> = [&options.context_column, &options.status_column]
+        .into_iter()
+        .filter_map(|c| c.as_deref().map(str::trim))
+        .filter(|c| !c.is_empty())
+        .collect();
+    let mut codes: IndexSet<String> = IndexSet::new();
+    for sheet_config in sheet_configs {
+        let Some(Ok(range)) = workbook.worksheet_range(&sheet_config.name) else {
+            continue;
+        };
+        let Ok(Some(header_row)) = sheet_config.header(&range) else {
+            continue;
+        };
+        let key_col = sheet_config.key_column.unwrap_or(key_column);
+        codes.extend(
+            header_row
+                .iter()
+                .enumerate()
+                .filter(|(col, _)| *col != key_col)
+                .map(|(_, c)| get_cell_string(c, ColumnType::Auto).trim().to_string())
+                .filter(|h| {
+                    !h.is_empty() && !is_metadata_header(h) && !known.contains(&h.as_str())
+                }),
+        );
+    }
+    codes
+        .into_iter()
+        .map(|code| {
+            parse_language_config(LanguageConfigEntry {
+                code,
+                ..Default::default()
+            })
+        })
+        .collect()
+}
+
+/// sheet 配置表中各配置项所在的列
+#[derive(Debug, Clone, Copy)]
+struct SheetConfigColumns {
+    name: usize,
+    sheet_type: Option<usize>,
+    column_types: Option<usize>,
+    key_column: Option<usize>,
+    header_row: Option<usize>,
+    json_path: Option<usize>,
+    transforms: Option<usize>,
+}
+
+impl SheetConfigColumns {
+    /// 没有表头时的旧格式: 名称、类型、列类型、key 列、表头行、json 路径依次排列，没有转换列
+    const LEGACY: Self = Self {
+        name: 0,
+        sheet_type: Some(1),
+        column_types: Some(2),
+        key_column: Some(3),
+        header_row: Some(4),
+        json_path: Some(5),
+        transforms: None,
+    };
+
+    /// 第一行第一列是名称表头 ("name" / "sheet" / "工作表") 时按表头名称映射各列，
+    /// 缺少的可选列使用默认值；否则返回 None，按旧的固定列位置读取
+    fn from_header(progress: &Progress, header_row: &[DataType]) -> Result<Option<Self>, String> {
+        let header = |c: &DataType| get_cell_string(c, ColumnType::Auto).trim().to_lowercase();
+        let is_name = |h: &str| matches!(h, "name" | "sheet" | "工作表");
+        if !header_row.first().is_some_and(|c| is_name(&header(c))) {
+            return Ok(None);
+        }
+
+        let mut columns = Self {
+            name: 0,
+            sheet_type: None,
+            column_types: None,
+            key_column: None,
+            header_row: None,
+            json_path: None,
+            transforms: None,
+        };
+        for (col, cell) in header_row.iter().enumerate().skip(1) {
+            match header(cell).as_str() {
+                "" => {}
+                "type" | "类型" => columns.sheet_type = Some(col),
+                "column_types" | "列类型" => columns.column_types = Some(col),
+                "key_column" | "key列" => columns.key_column = Some(col),
+                "header_row" | "表头行" => columns.header_row = Some(col),
+                "json_path" | "json路径" => columns.json_path = Some(col),
+                "transforms" | "转换" => columns.transforms = Some(col),
+                other => send_progress(
+                    progress,
+                    &format!("⚠️ sheet 配置中无法识别的列: '{}'，已忽略", other),
+                    LogType::Warning,
+                )?,
+            }
+        }
+        Ok(Some(columns))
+    }
+}
+
+fn read_sheet_configs_from_excel<RS, R>(
+    progress: &Progress,
+    workbook: &mut R,
+    sheet_name: &str,
+) -> Result<Vec<SheetConfig>, ExportError>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+    R::Error: std::fmt::Display,
+{
+    let range = read_config_sheet(workbook, sheet_name)?;
+
+    let mut rows = range.rows().peekable();
+    let columns = match rows.peek() {
+        Some(header_row) => match SheetConfigColumns::from_header(progress, header_row)? {
+            Some(columns) => {
+                rows.next();
+                columns
+            }
+            None => SheetConfigColumns::LEGACY,
+        },
+        None => SheetConfigColumns::LEGACY,
+    };
+    let cell = |row: &[DataType], col: Option<usize>| {
+        col.and_then(|c| row.get(c))
+            .map(|c| get_cell_string(c, ColumnType::Auto))
+            .filter(|s| !s.trim().is_empty())
+    };
+
+    let mut configs = Vec::new();
+
+    for row in rows {
+        if row.is_empty() {
+            continue;
+        }
+        let entry = SheetConfigEntry {
+            name: cell(row, Some(columns.name)).unwrap_or_default(),
+            sheet_type: cell(row, columns.sheet_type),
+            column_types: cell(row, columns.column_types),
+            key_column: cell(row, columns.key_column),
+            header_row: cell(row, columns.header_row),
+            json_path: cell(row, columns.json_path),
+            transforms: cell(row, columns.transforms),
+        };
+        configs.extend(parse_sheet_config(progress, entry)?);
+    }
+
+    Ok(configs)
+}
+
+/// 一条 sheet 配置，来自 sheet 配置表的一行或外部配置文件。
+/// 外部配置文件中 key 列和表头行可以直接写数字
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct SheetConfigEntry {
+    name: String,
+    #[serde(rename = "type")]
+    sheet_type: Option<String>,
+    column_types: Option<String>,
+    #[serde(deserialize_with = "string_or_number")]
+    key_column: Option<String>,
+    #[serde(deserialize_with = "string_or_number")]
+    header_row: Option<String>,
+    json_path: Option<String>,
+    transforms: Option<String>,
+}
+
+fn string_or_number<'de, D: serde::Deserializer<'de>>(d: D) -> Result<Option<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Raw {
+        String(String),
+        Number(i64),
+    }
+    Ok(Option::<Raw>::deserialize(d)?.map(|raw| match raw {
+        Raw::String(s) => s,
+        Raw::Number(n) => n.to_string(),
+    }))
+}
+
+/// 校验并转换 sheet 配置，空字符串视为未填；ignore 的配置返回 None
+fn parse_sheet_config(
+    progress: &Progress,
+    entry: SheetConfigEntry,
+) -> Result<Option<SheetConfig>, ExportError> {
+    let field = |v: Option<String>| v.filter(|s| !s.trim().is_empty());
+    let name = entry.name;
+    let sheet_type = field(entry.sheet_type);
+
+    // 类型为 ignore 或名称以 # 开头的配置行暂不导出，不用删除配置
+    if sheet_type
+        .as_deref()
+        .is_some_and(|t| t.trim().eq_ignore_ascii_case("ignore"))
+        || name.trim_start().starts_with('#')
+    {
+        send_progress(
+            progress,
+            &format!(
+                "已忽略工作表: {}",
+                name.trim_start().trim_start_matches('#')
+            ),
+            LogType::Info,
+        )?;
+        return Ok(None);
+    }
+
+    // 列类型声明，格式如 "en:number,zh:date"
+    let mut column_types = HashMap::new();
+    if let Some(declarations) = field(entry.column_types) {
+        for item in declarations.split([',', ';']) {
+            if item.trim().is_empty() {
+                continue;
+            }
+            let parsed = item
+                .split_once(':')
+                .and_then(|(header, ty)| Some((header.trim(), ColumnType::parse(ty)?)));
+            match parsed {
+                Some((header, ty)) => {
+                    column_types.insert(header.to_string(), ty);
+                }
+                None => send_progress(
+                    progress,
+                    &format!("⚠️ 工作表 {} 的列类型声明无效: '{}'", name, item.trim()),
+                    LogType::Warning,
+                )?,
+            }
+        }
+    }
+
+    // key 所在列，格式同 parse_column_index
+    let key_column = match field(entry.key_column) {
+        Some(s) => Some(
+            parse_column_index(&s)
+                .ok_or_else(|| format!("工作表 {} 的 key 列配置无效: '{}'", name, s.trim()))?,
+        ),
+        None => None,
+    };
+
+    // 表头所在的行号，从 1 开始，与 Excel 中显示的一致
+    let header_row = match field(entry.header_row) {
+        Some(s) => s
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .and_then(|n| n.checked_sub(1))
+            .ok_or_else(|| format!("工作表 {} 的表头行配置无效: '{}'", name, s.trim()))?,
+        None => 0,
+    };
+
+    // 输出中的父级路径，如 "ui.menu"；不填时嵌套在工作表名称下
+    let json_path = match field(entry.json_path) {
+        Some(s) => {
+            let path = s.trim().to_string();
+            if split_dotted_key(&path).iter().any(|p| p.is_empty()) {
+                return Err(format!("工作表 {} 的 json 路径配置无效: '{}'", name, path).into());
+            }
+            path
+        }
+        None => name.replace('.', "\\."),
+    };
+
+    // 值转换，格式如 "replace:...:…; en=uppercase|trim"，不写语言的对所有语言列生效
+    let mut transforms = Vec::new();
+    if let Some(spec) = field(entry.transforms) {
+        for item in spec.split([';', '\n']) {
+            let item = item.trim_start();
+            if item.trim().is_empty() {
+                continue;
+            }
+            // `=` 出现在第一个操作的冒号之前时，前面是语言
+            let (lang, ops) = match item.split_once('=') {
+                Some((lang, ops)) if !lang.contains(':') => (Some(lang.trim().to_string()), ops),
+                _ => (None, item),
+            };
+            for op in ops.split('|') {
+                let parsed = TransformOp::parse(op).ok_or_else(|| {
+                    format!(
+                        "工作表 {} 的转换配置无效: '{}' (可选: uppercase / lowercase / trim / replace:from:to)",
+                        name,
+                        op.trim()
+                    )
+                })?;
+                transforms.push((lang.clone(), parsed));
+            }
+        }
+    }
+
+    Ok(Some(SheetConfig {
+        name,
+        sheet_type,
+        column_types,
+        key_column,
+        header_row,
+        json_path,
+        transforms,
+    }))
+}
+
+/// Excel 日期序列号 (天数 + 秒数) 对应的时间，超出可表示的日期范围时为 None
+fn excel_datetime(days: i64, seconds: i64) -> Option<NaiveDateTime> {
+    let base = NaiveDate::from_ymd_opt(1899, 12, 30)?.and_hms_opt(0, 0, 0)?;
+    base.checked_add_signed(ChronoDuration::try_days(days)?)?
+        .checked_add_signed(ChronoDuration::try_seconds(seconds)?)
+}
+
+/// 将 Excel 日期序列号格式化为 `%Y-%m-%d`，超出日期范围时按数字输出
+fn format_excel_date(serial: f64) -> String {
+    match excel_datetime(serial as i64, 0) {
+        Some(date) => date.format("%Y-%m-%d").to_string(),
+        None => format_number(serial),
+    }
+}
+
+/// 按日期输出但超出日期范围的单元格 (如 date 列中的大数字 ID)，返回其数值
+fn out_of_range_date(cell: &DataType, hint: ColumnType) -> Option<f64> {
+    let serial = match cell {
+        DataType::Float(f) if is_date_serial(*f, hint) => *f,
+        DataType::Int(i) if matches!(hint, ColumnType::Date | ColumnType::LocalDate) => *i as f64,
+        DataType::DateTime(dt) if !matches!(hint, ColumnType::Number | ColumnType::LocalNumber) => {
+            *dt
+        }
+        _ => return None,
+    };
+    let seconds = (serial.fract() * 86400.0) as i64;
+    excel_datetime(serial.trunc() as i64, seconds)
+        .is_none()
+        .then_some(serial)
+}
+
+/// local_date / local_number 列按语言配置的格式输出；语言没有配置对应格式时与 date / number 相同
+fn get_localized_cell_string(cell: &DataType, hint: ColumnType, lang: &LanguageConfig) -> String {
+    let serial = match cell {
+        DataType::Float(f) | DataType::DateTime(f) => Some(*f),
+        DataType::Int(i) => Some(*i as f64),
+        _ => None,
+    };
+    match (hint, serial, &lang.date_format, lang.number_format) {
+        (ColumnType::LocalDate, Some(serial), Some(format), _) => {
+            let seconds = (serial.fract() * 86400.0).round() as i64;
+            match excel_datetime(serial.trunc() as i64, seconds) {
+                // 日期格式在读取语言配置时已经校验过
+                Some(datetime) => datetime.format(format).to_string(),
+                None => format_number(serial),
+            }
+        }
+        (ColumnType::LocalNumber, Some(f), _, Some(format)) => format.format(f),
+        _ => get_cell_string(cell, hint),
+    }
+}
+
+fn format_number(f: f64) -> String {
+    if f.fract() == 0.0 {
+        format!("{:.0}", f)
+    } else {
+        f.to_string()
+    }
+}
+
+fn is_date_serial(f: f64, hint: ColumnType) -> bool {
+    match hint {
+        ColumnType::Auto => f > 30000.0 && f < 70000.0,
+        ColumnType::Date | ColumnType::LocalDate => true,
+        ColumnType::Number | ColumnType::LocalNumber => false,
+    }
+}
+
+/// preserve_types 开启时真正的布尔和数字单元格保留类型，
+/// 按日期输出的数字仍然是字符串
+fn typed_cell_value(cell: &DataType, hint: ColumnType) -> Option<CellValue> {
+    match cell {
+        DataType::Bool(b) => Some(CellValue::Bool(*b)),
+        // 本地化的数字是给人看的文本，不保留类型
+        _ if hint == ColumnType::LocalNumber => None,
+        DataType::Int(i) if !matches!(hint, ColumnType::Date | ColumnType::LocalDate) => {
+            Some(CellValue::Number(*i as f64))
+        }
+        DataType::Float(f) if !is_date_serial(*f, hint) => Some(CellValue::Number(*f)),
+        _ => None,
+    }
+}
+
+/// 单元格转字符串，`hint` 为该列声明的类型，声明过的类型优先于默认的日期猜测
+fn get_cell_string(cell: &DataType, hint: ColumnType) -> String {
+    match cell {
+        DataType::String(s) => s.to_string(),
+        DataType::Float(f) => {
+            if is_date_serial(*f, hint) {
+                format_excel_date(*f)
+            } else {
+                format_number(*f)
+            }
+        }
+        DataType::Int(i) => match hint {
+            ColumnType::Date | ColumnType::LocalDate => format_excel_date(*i as f64),
+            _ => i.to_string(),
+        },
+        DataType::Bool(b) => b.to_string(),
+        DataType::DateTime(dt) if matches!(hint, ColumnType::Number | ColumnType::LocalNumber) => {
+            format_number(*dt)
+        }
+        DataType::DateTime(dt) => {
+            let days = dt.trunc() as i64;
+            let seconds = ((*dt - dt.trunc()) * 86400.0) as i64;
+            match excel_datetime(days, seconds) {
+                Some(datetime) => datetime.format("%Y-%m-%d %H:%M:%S").to_string(),
+                None => format_number(*dt),
+            }
+        }
+        DataType::Duration(d) => {
+            let total_seconds = (*d * 86400.0) as i64;
+            let hours = total_seconds / 3600;
+            let minutes = (total_seconds % 3600) / 60;
+            let seconds = total_seconds % 60;
+            format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+        }
+        DataType::DateTimeIso(s) => s.clone(),
+        DataType::DurationIso(s) => s.clone(),
+        DataType::Error(e) => format!("Error: {:?}", e),
+        DataType::Empty => String::new(),
+    }
+}
+
+/// 检查目录存在且可写，通过创建并删除一个临时文件来验证
+fn ensure_writable_dir(dir: &Path) -> Result<(), String> {
+    if !dir.is_dir() {
+        return Err(format!("输出目录不存在: {}", dir.display()));
+    }
+    let probe = dir.join(format!(".excel2json_write_test_{}", std::process::id()));
+    File::create(&probe).map_err(|e| format!("输出目录不可写: {} ({})", dir.display(), e))?;
+    let _ = fs::remove_file(&probe);
+    Ok(())
+}
+
+/// zip 压缩方式及等级
+#[derive(Debug, Clone, Copy)]
+struct ZipCompression {
+    method: CompressionMethod,
+    level: Option<i32>,
+}
+
+/// 导出文件的编码方式
+#[derive(Debug, Clone, Copy)]
+struct OutputEncoding {
+    encoding: &'static Encoding,
+    bom: bool,
+}
+
+impl OutputEncoding {
+    /// 将内容转为要写入的字节，目标编码无法表示的字符会报错而不是被替换
+    fn encode(&self, content: &str) -> Result<Vec<u8>, String> {
+        let mut bytes = Vec::new();
+        if self.bom {
+            bytes.extend_from_slice("\u{FEFF}".as_bytes());
+        }
+        if self.encoding == UTF_8 {
+            bytes.extend_from_slice(content.as_bytes());
+            return Ok(bytes);
+        }
+        let (encoded, _, had_errors) = self.encoding.encode(content);
+        if had_errors {
+            let mut buf = [0; 4];
+            let ch = content
+                .chars()
+                .find(|c| self.encoding.encode(c.encode_utf8(&mut buf)).2)
+                .unwrap_or_default();
+            return Err(format!(
+                "内容包含 {} 编码无法表示的字符 '{}'",
+                self.encoding.name(),
+                ch
+            ));
+        }
+        bytes.extend_from_slice(&encoded);
+        Ok(bytes)
+    }
+}
+
+fn parse_output_encoding(
+    label: Option<&str>,
+    bom: bool,
+    formats: &[OutputFormat],
+) -> Result<OutputEncoding, String> {
+    let encoding = match label.map(str::trim) {
+        None | Some("") => UTF_8,
+        Some(label) => Encoding::for_label(label.as_bytes())
+            // UTF-16 等编码 encoding_rs 只能解码不能编码
+            .filter(|e| e.output_encoding() == *e)
+            .ok_or_else(|| format!("不支持的输出编码: {}", label))?,
+    };
+    if encoding != UTF_8 {
+        if bom {
+            return Err("BOM 只能用于 UTF-8 编码".to_string());
+        }
+        if formats.iter().any(|f| {
+            matches!(
+                f,
+                OutputFormat::Android | OutputFormat::Ios | OutputFormat::Po
+            )
+        }) {
+            return Err(format!(
+                "android / ios / po 格式的文件头声明了 UTF-8，不能使用 {} 编码",
+                encoding.name()
+            ));
+        }
+    }
+    Ok(OutputEncoding { encoding, bom })
+}
+
+/// 解析压缩参数并校验等级范围: deflated/bzip2 为 0~9，zstd 为 -7~22，stored 不接受等级
+fn parse_zip_compression(method: &str, level: Option<i32>) -> Result<ZipCompression, String> {
+    let (method, range) = match method.trim().to_lowercase().as_str() {
+        "" | "deflated" | "deflate" => (CompressionMethod::Deflated, Some(0..=9)),
+        "stored" | "none" => (CompressionMethod::Stored, None),
+        "bzip2" => (CompressionMethod::Bzip2, Some(0..=9)),
+        "zstd" => (CompressionMethod::Zstd, Some(-7..=22)),
+        other => return Err(format!("不支持的压缩方式: {}", other)),
+    };
+    if let Some(level) = level {
+        match range {
+            Some(range) if range.contains(&level) => {}
+            Some(range) => {
+                return Err(format!(
+                    "压缩等级 {} 超出范围 {}~{}",
+                    level,
+                    range.start(),
+                    range.end()
+                ))
+            }
+            None => return Err("stored 模式不支持设置压缩等级".to_string()),
+        }
+    }
+    Ok(ZipCompression { method, level })
+}
+
+/// 导出文件夹的归档格式
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ArchiveFormat {
+    Zip,
+    TarGz,
+    None,
+}
+
+impl ArchiveFormat {
+    fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::Zip => "zip",
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::None => "",
+        }
+    }
+}
+
+/// skip_zip 优先于 archive_format，保持旧配置的行为
+fn parse_archive_format(value: Option<&str>, skip_zip: bool) -> Result<ArchiveFormat, String> {
+    if skip_zip {
+        return Ok(ArchiveFormat::None);
+    }
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("zip") => Ok(ArchiveFormat::Zip),
+        Some("tar.gz") | Some("tgz") => Ok(ArchiveFormat::TarGz),
+        Some("none") => Ok(ArchiveFormat::None),
+        Some(other) => Err(format!("不支持的归档格式: {}", other)),
+    }
+}
+
+const CHECKSUM_FILE: &str = "checksums.sha256";
+
+/// 生成 sha256sum 格式的校验和列表: 每行 `<十六进制哈希>  <相对路径>`，
+/// 可以在导出目录中用 `sha256sum -c checksums.sha256` 校验
+fn sha256_manifest(output_dir: &Path, relative_paths: &[String]) -> Result<String, String> {
+    let mut manifest = String::new();
+    for relative in relative_paths {
+        let mut file = File::open(output_dir.join(relative))
+            .map_err(|e| format!("计算校验和失败: {}: {}", relative, e))?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)
+            .map_err(|e| format!("计算校验和失败: {}: {}", relative, e))?;
+        manifest.push_str(&format!("{:x}  {}\n", hasher.finalize(), relative));
+    }
+    Ok(manifest)
+}
+
+/// 归档时包含的文件: (归档内的路径, 文件路径)，按归档内路径排序。
+/// 先收集文件，才能报告 "第几个 / 共几个"；WalkDir 的遍历顺序依赖文件系统，排序后归档才可复现
+fn archive_entries(src_dir: &Path) -> Result<Vec<(String, PathBuf)>, String> {
+    let base_path = src_dir.parent().unwrap_or_else(|| Path::new(""));
+    let mut files = Vec::new();
+    for entry in WalkDir::new(src_dir) {
+        let entry = entry.map_err(|e| format!("读取目录失败: {}", e))?;
+        if entry.path().is_file() {
+            // 计算相对路径（去掉上级目录），归档内统一使用 / 分隔
+            let path = entry.into_path();
+            let name = path
+                .strip_prefix(base_path)
+                .unwrap()
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((name, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// 压缩整个文件夹为 zip 文件。
+/// 传入 progress 时每写入一个文件发送一次进度，包含序号、文件名和累计写入的字节数。
+/// `incremental` 时复用已有 zip 中没有变化的条目，先写入临时文件，完成后替换旧 zip
+fn zip_directory(
+    progress: Option<&Progress>,
+    src_dir: &Path,
+    dst_file: &Path,
+    compression: ZipCompression,
+    incremental: bool,
+) -> Result<(), String> {
+    let files = archive_entries(src_dir)?;
+    let mut previous = match incremental {
+        true => open_previous_zip(progress, dst_file, &files, compression)?,
+        false => None,
+    };
+    let tmp_file = dst_file.with_file_name(format!(
+        "{}.tmp",
+        dst_file.file_name().unwrap_or_default().to_string_lossy()
+    ));
+    let target = if previous.is_some() {
+        tmp_file.as_path()
+    } else {
+        dst_file
+    };
+    let file = File::create(target).map_err(|e| format!("创建 zip 文件失败: {}", e))?;
+    let written = write_zip_entries(progress, file, &files, compression, previous.as_mut())
+        .and_then(|reused| match previous.take() {
+            Some(_) => fs::rename(&tmp_file, dst_file)
+                .map(|_| Some(reused))
+                .map_err(|e| format!("替换 zip 文件失败: {}", e)),
+            None => Ok(None),
+        });
+    let reused = match written {
+        Ok(reused) => reused,
+        Err(e) => {
+            // 失败或取消时不留下写了一半的文件，增量更新时旧 zip 保持不变
+            let _ = fs::remove_file(target);
+            return Err(e);
+        }
+    };
+    if let (Some(reused), Some(progress)) = (reused, progress) {
+        send_progress(
+            progress,
+            &format!(
+                "已增量更新 zip: {} 个条目没有变化, 重新压缩 {} 个",
+                reused,
+                files.len() - reused
+            ),
+            LogType::Info,
+        )?;
+    }
+    Ok(())
+}
+
+/// 写入 zip 的注释，记录压缩方式和等级。条目中只记录了压缩方式，
+/// 增量更新时据此判断旧条目是否按同样的设置压缩
+fn zip_comment(compression: ZipCompression) -> String {
+    match compression.level {
+        Some(level) => format!("excel2json {:?} level {}", compression.method, level),
+        None => format!("excel2json {:?}", compression.method),
+    }
+}
+
+/// 逐个写入 zip 条目，`previous` 中内容没有变化的条目直接复制，返回复制的条目数
+fn write_zip_entries(
+    progress: Option<&Progress>,
+    file: File,
+    files: &[(String, PathBuf)],
+    compression: ZipCompression,
+    mut previous: Option<&mut zip::ZipArchive<BufReader<File>>>,
+) -> Result<usize, String> {
+    let mut zip = zip::ZipWriter::new(file);
+    zip.set_comment(zip_comment(compression));
+    // 条目使用固定的修改时间 (1980-01-01)，内容相同时生成的 zip 逐字节一致
+    let options = FileOptions::default()
+        .compression_method(compression.method)
+        .compression_level(compression.level)
+        .last_modified_time(zip::DateTime::default());
+
+    let mut total_bytes: u64 = 0;
+    let mut reused = 0;
+    for (i, (name_str, path)) in files.iter().enumerate() {
+        let content = fs::read(path).map_err(|e| format!("读取文件失败: {}", e))?;
+        let unchanged = previous
+            .as_mut()
+            .and_then(|archive| archive.by_index_raw(i).ok())
+            .filter(|entry| {
+                entry.compression() == compression.method
+                    && entry.size() == content.len() as u64
+                    && entry.crc32() == crc32fast::hash(&content)
+            });
+        if let Some(entry) = unchanged {
+            zip.raw_copy_file(entry)
+                .map_err(|e| format!("复制 zip 条目失败: {}", e))?;
+            reused += 1;
+        } else {
+            zip.start_file(name_str.clone(), options)
+                .map_err(|e| format!("写入 zip 条目失败: {}", e))?;
+            zip.write_all(&content)
+                .map_err(|e| format!("写入 zip 内容失败: {}", e))?;
+        }
+        total_bytes += content.len() as u64;
+
+        if let Some(progress) = progress {
+            send_progress(
+                progress,
+                &format!(
+                    "正在压缩 {}/{}: {} (累计 {} 字节)",
+                    i + 1,
+                    files.len(),
+                    name_str,
+                    total_bytes
+                ),
+                LogType::Info,
+            )?;
+        }
+    }
+
+    zip.finish().map_err(|e| format!("关闭 zip 失败: {}", e))?;
+    Ok(reused)
+}
+
+/// 打开用于增量更新的旧 zip。压缩设置和条目的名称、顺序必须与这次完全一致，
+/// 否则返回 None 完整重建
+fn open_previous_zip(
+    progress: Option<&Progress>,
+    dst_file: &Path,
+    files: &[(String, PathBuf)],
+    compression: ZipCompression,
+) -> Result<Option<zip::ZipArchive<BufReader<File>>>, String> {
+    let Ok(file) = File::open(dst_file) else {
+        return Ok(None);
+    };
+    let reason = match zip::ZipArchive::new(BufReader::new(file)) {
+        Ok(archive) if archive.comment() != zip_comment(compression).as_bytes() => {
+            "压缩设置有变化".to_string()
+        }
+        Ok(mut archive) => {
+            let same_entries = archive.len() == files.len()
+                && files
+                    .iter()
+                    .enumerate()
+                    .all(|(i, (name, _))| archive.by_index_raw(i).is_ok_and(|e| e.name() == name));
+            if same_entries {
+                return Ok(Some(archive));
+            }
+            "条目列表有变化".to_string()
+        }
+        Err(e) => format!("无法读取: {}", e),
+    };
+    if let Some(progress) = progress {
+        send_progress(
+            progress,
+            &format!("已有的 zip {}, 完整重建: {}", reason, dst_file.display()),
+            LogType::Info,
+        )?;
+    }
+    Ok(None)
+}
+
+/// 打包整个文件夹为 tar.gz，内部路径和顺序与 zip 相同。
+/// 属主、权限和修改时间都是固定值，内容相同时归档逐字节一致；超过 100 字节的路径使用 GNU 长路径扩展。
+/// 传入 progress 时每写入一个文件发送一次进度
+fn tar_gz_directory(
+    progress: Option<&Progress>,
+    src_dir: &Path,
+    dst_file: &Path,
+) -> Result<(), String> {
+    let file = File::create(dst_file).map_err(|e| format!("创建 tar.gz 文件失败: {}", e))?;
+    // gzip 头中的修改时间默认为 0，同样不影响复现
+    let mut tar = tar::Builder::new(GzEncoder::new(file, flate2::Compression::default()));
+    let files = archive_entries(src_dir)?;
+    let mut total_bytes: u64 = 0;
+    for (i, (name, path)) in files.iter().enumerate() {
+        let mut f = File::open(path).map_err(|e| format!("打开文件失败: {}", e))?;
+        let size = f
+            .metadata()
+            .map_err(|e| format!("读取文件失败: {}", e))?
+            .len();
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Regular);
+        header.set_size(size);
+        header.set_mode(0o644);
+        header.set_uid(0);
+        header.set_gid(0);
+        header.set_mtime(0);
+        tar.append_data(&mut header, name, &mut f)
+            .map_err(|e| format!("写入 tar.gz 内容失败: {}", e))?;
+        total_bytes += size;
+
+        if let Some(progress) = progress {
+            send_progress(
+                progress,
+                &format!(
+                    "正在压缩 {}/{}: {} (累计 {} 字节)",
+                    i + 1,
+                    files.len(),
+                    name,
+                    total_bytes
+                ),
+                LogType::Info,
+            )?;
+        }
+    }
+    tar.into_inner()
+        .and_then(|gz| gz.finish().map(|_| ()))
+        .map_err(|e| format!("关闭 tar.gz 失败: {}", e))
+}
+
+/// 导出目录中由本工具生成、不属于任何语言的 JSON 文件。
+/// diff.json 由旧版本的对比命令写入导出目录，仍然跳过
+const NON_LANGUAGE_JSON_FILES: [&str; 8] = [
+    "report.json",
+    "diff.json",
+    "meta.json",
+    "schema.json",
+    "comments.json",
+    "notes.json",
+    "template.json",
+    "all.json",
+];
+
+/// 语言代码 -> 点分 key -> 值
+type FlatLanguages = IndexMap<String, IndexMap<String, String>>;
+
+/// 按导出时的文件名模式 (如 `{lang}.{ext}`、`locales/{lang}/translation.{ext}`) 匹配语言文件的相对路径，
+/// 第一个分组为语言代码。按工作表拆分的模式 (含 `{sheet}`) 不支持
+fn language_file_regex(pattern: &str) -> Result<Regex, String> {
+    if pattern.contains("{sheet}") {
+        return Err(format!(
+            "不支持读取按工作表拆分的导出 (文件名模式 '{}'), 请使用合并输出的导出目录",
+            pattern
+        ));
+    }
+    let mut regex = String::from("^");
+    for (i, part) in pattern.replace("{ext}", "json").split("{lang}").enumerate() {
+        if i > 0 {
+            regex.push_str("([^/]+)");
+        }
+        regex.push_str(&regex::escape(part));
+    }
+    regex.push('$');
+    Regex::new(&regex).map_err(|e| format!("文件名模式 '{}' 无效: {}", pattern, e))
+}
+
+/// 读取目录中按 `pattern` 命名的语言 JSON 并展开为点分 key，按文件路径排序
+fn read_json_languages(
+    progress: &Progress,
+    dir: &Path,
+    pattern: &str,
+) -> Result<FlatLanguages, ExportError> {
+    if !dir.is_dir() {
+        let msg = format!("目录不存在: {}", dir.display());
+        send_progress(progress, &msg, LogType::Error)?;
+        return Err(msg.into());
+    }
+
+    let file_re = language_file_regex(pattern)?;
+    let mut json_files: Vec<(String, PathBuf)> = Vec::new();
+    for entry in WalkDir::new(dir) {
+        let entry = entry.map_err(|e| format!("读取目录失败: {}", e))?;
+        if !entry.path().is_file() {
+            continue;
+        }
+        let relative = entry
+            .path()
+            .strip_prefix(dir)
+            .unwrap()
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        if NON_LANGUAGE_JSON_FILES.contains(&relative.as_str()) {
+            continue;
+        }
+        if let Some(caps) = file_re.captures(&relative) {
+            json_files.push((caps[1].to_string(), entry.into_path()));
+        }
+    }
+    json_files.sort_by(|a, b| a.1.cmp(&b.1));
+    if json_files.is_empty() {
+        let msg = format!(
+            "目录中没有符合文件名模式 '{}' 的 JSON 文件: {}",
+            pattern,
+            dir.display()
+        );
+        send_progress(progress, &msg, LogType::Error)?;
+        return Err(msg.into());
+    }
+
+    let mut languages = FlatLanguages::new();
+    for (code, file) in json_files {
+        let content = fs::read_to_string(&file)
+            .map_err(|e| format!("读取 {} 失败: {}", file.display(), e))?;
+        let parsed =
+            json::parse(&content).map_err(|e| format!("解析 {} 失败: {}", file.display(), e))?;
+
+        let mut flat = IndexMap::new();
+        flatten_json(&parsed, "", &mut flat);
+        send_progress(
+            progress,
+            &format!("已读取语言 {}: {} 个 key", code, flat.len()),
+            LogType::Info,
+        )?;
+        languages.insert(code, flat);
+    }
+    Ok(languages)
+}
+
+/// 读取目录中所有 `<语言>.json`，合并为一个 key 列 + 每个语言一列的 xlsx
+#[tauri::command]
+async fn convert_json_to_excel(
+    app: AppHandle,
+    json_dir: String,
+    output_xlsx: String,
+) -> Result<String, ExportError> {
+    convert_json_dir_to_excel(
+        &app_progress(&app),
+        Path::new(&json_dir),
+        Path::new(&output_xlsx),
+    )
+}
+
+/// convert_json_to_excel 的实现: 嵌套的 key 展开为点分 key，返回可读的结果描述
+pub fn convert_json_dir_to_excel(
+    progress: &Progress,
+    dir: &Path,
+    output_xlsx: &Path,
+) -> Result<String, ExportError> {
+    let languages = read_json_languages(progress, dir, DEFAULT_FILENAME_PATTERN)?;
+    let mut all_keys: IndexSet<String> = IndexSet::new();
+    for flat in languages.values() {
+        all_keys.extend(flat.keys().cloned());
+    }
+
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let worksheet = workbook.add_worksheet();
+    let xlsx_err = |e: rust_xlsxwriter::XlsxError| format!("写入 Excel 失败: {}", e);
+
+    worksheet.write_string(0, 0, "key").map_err(xlsx_err)?;
+    for (col, (code, _)) in languages.iter().enumerate() {
+        worksheet
+            .write_string(0, (col + 1) as u16, code)
+            .map_err(xlsx_err)?;
+    }
+    for (row, key) in all_keys.iter().enumerate() {
+        let row = (row + 1) as u32;
+        worksheet.write_string(row, 0, key).map_err(xlsx_err)?;
+        for (col, (_, flat)) in languages.iter().enumerate() {
+            if let Some(value) = flat.get(key) {
+                worksheet
+                    .write_string(row, (col + 1) as u16, value)
+                    .map_err(xlsx_err)?;
+            }
+        }
+    }
+    workbook.save(output_xlsx).map_err(xlsx_err)?;
+
+    let msg = format!(
+        "完成转换 {} 个语言, {} 个 key, 已保存为 {}",
+        languages.len(),
+        all_keys.len(),
+        output_xlsx.display()
+    );
+    send_progress(progress, &format!("✅ {}", msg), LogType::Success)?;
+    Ok(msg)
+}
+
+/// 同一个 key 修改前后的值
+#[derive(Serialize, Debug)]
+pub struct ValueChange {
+    pub old: String,
+    pub new: String,
+}
+
+/// 单个语言两次导出之间的差异，key 为点分路径
+#[derive(Serialize, Default, Debug)]
+pub struct LanguageDiff {
+    pub language: String,
+    pub added: IndexMap<String, String>,
+    pub removed: IndexMap<String, String>,
+    pub changed: IndexMap<String, ValueChange>,
+}
+
+impl LanguageDiff {
+    fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// 两个导出目录的差异，只在一侧存在的语言视为全部新增或全部删除
+#[derive(Serialize, Default, Debug)]
+pub struct ExportDiff {
+    pub languages: Vec<LanguageDiff>,
+    pub added_count: usize,
+    pub removed_count: usize,
+    pub changed_count: usize,
+}
+
+/// 按点分 key 对比两个导出目录中的语言 JSON，`filename_pattern` 为导出时的文件名模式，
+/// 不填时为 `{lang}.{ext}`
+pub fn diff_export_dirs(
+    progress: &Progress,
+    old_dir: &Path,
+    new_dir: &Path,
+    filename_pattern: Option<&str>,
+) -> Result<ExportDiff, ExportError> {
+    let pattern = filename_pattern
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .unwrap_or(DEFAULT_FILENAME_PATTERN);
+    let old_languages = read_json_languages(progress, old_dir, pattern)?;
+    let new_languages = read_json_languages(progress, new_dir, pattern)?;
+    let codes: IndexSet<&String> = old_languages.keys().chain(new_languages.keys()).collect();
+
+    let empty = IndexMap::new();
+    let mut diff = ExportDiff::default();
+    for code in codes {
+        let old = old_languages.get(code).unwrap_or(&empty);
+        let new = new_languages.get(code).unwrap_or(&empty);
+        let mut lang_diff = LanguageDiff {
+            language: code.clone(),
+            ..Default::default()
+        };
+        for (key, new_value) in new {
+            match old.get(key) {
+                None => {
+                    lang_diff.added.insert(key.clone(), new_value.clone());
+                }
+                Some(old_value) if old_value != new_value => {
+                    lang_diff.changed.insert(
+                        key.clone(),
+                        ValueChange {
+                            old: old_value.clone(),
+                            new: new_value.clone(),
+                        },
+                    );
+                }
+                Some(_) => {}
+            }
+        }
+        for (key, old_value) in old {
+            if !new.contains_key(key) {
+                lang_diff.removed.insert(key.clone(), old_value.clone());
+            }
+        }
+
+        let log_type = if lang_diff.is_empty() {
+            LogType::Info
+        } else {
+            LogType::Warning
+        };
+        send_progress(
+            progress,
+            &format!(
+                "语言 {}: 新增 {} 个, 删除 {} 个, 修改 {} 个",
+                code,
+                lang_diff.added.len(),
+                lang_diff.removed.len(),
+                lang_diff.changed.len()
+            ),
+            log_type,
+        )?;
+        diff.added_count += lang_diff.added.len();
+        diff.removed_count += lang_diff.removed.len();
+        diff.changed_count += lang_diff.changed.len();
+        diff.languages.push(lang_diff);
+    }
+    Ok(diff)
+}
+
+/// 对比两次导出并返回给前端；指定 `output_path` 时同时把差异写入该 JSON 文件，
+/// 不会在导出目录中写入任何文件
+#[tauri::command]
+async fn diff_exports(
+    app: AppHandle,
+    old_dir: String,
+    new_dir: String,
+    filename_pattern: Option<String>,
+    output_path: Option<String>,
+) -> Result<ExportDiff, ExportError> {
+    let progress = app_progress(&app);
+    let diff = diff_export_dirs(
+        &progress,
+        Path::new(&old_dir),
+        Path::new(&new_dir),
+        filename_pattern.as_deref(),
+    )?;
+
+    let summary = format!(
+        "✅ 对比完成: {} 个语言, 新增 {} 个, 删除 {} 个, 修改 {} 个",
+        diff.languages.len(),
+        diff.added_count,
+        diff.removed_count,
+        diff.changed_count
+    );
+    match output_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(path) => {
+            let content = serde_json::to_string_pretty(&diff)
+                .map_err(|e| format!("生成差异报告失败: {}", e))?;
+            fs::write(path, content).map_err(|e| format!("写入差异报告失败: {}", e))?;
+            send_progress(
+                &progress,
+                &format!("{}, 已生成差异报告: {}", summary, path),
+                LogType::Success,
+            )?;
+        }
+        None => send_progress(&progress, &summary, LogType::Success)?,
+    }
+    Ok(diff)
+}
+
+/// 表头以 `#` 开头的列 (如 `#notes`) 是给译者看的备注等元数据列
+fn is_metadata_header(header: &str) -> bool {
+    header.trim_start().starts_with('#')
+}
+
+/// 在表头中查找语言列。
+///
+/// 匹配规则: 表头文本与语言代码完全相等 (区分大小写, 不去除空白)；
+/// 元数据列即使文本恰好相同也会被跳过, 因此永远不会被当作语言导出。
+fn find_language_column(header_row: &[DataType], code: &str) -> Option<usize> {
+    header_row.iter().position(|c| {
+        let header = get_cell_string(c, ColumnType::Auto);
+        !is_metadata_header(&header) && header == code
+    })
+}
+
+/// 每个配置的工作表只解析一次，缓存为 工作表名 -> Range，
+/// 读取失败或不存在的工作表只报告一次并跳过
+fn cache_sheet_ranges<RS, R>(
+    progress: &Progress,
+    workbook: &mut R,
+    sheet_configs: &[SheetConfig],
+    report: &mut ExportReport,
+) -> Result<HashMap<String, Range<DataType>>, String>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+    R::Error: std::fmt::Display,
+{
+    let mut ranges = HashMap::new();
+    for sheet_config in sheet_configs {
+        if ranges.contains_key(&sheet_config.name) {
+            continue;
+        }
+        let message = match workbook.worksheet_range(&sheet_config.name) {
+            Some(Ok(range)) => {
+                ranges.insert(sheet_config.name.clone(), range);
+                continue;
+            }
+            Some(Err(e)) => format!("⚠️ 读取工作表 {} 失败: {}", sheet_config.name, e),
+            None => format!("⚠️ 找不到工作表: {}", sheet_config.name),
+        };
+        report.record(
+            progress,
+            Issue {
+                category: IssueCategory::SheetError,
+                sheet: Some(sheet_config.name.clone()),
+                message,
+                ..Default::default()
+            },
+        )?;
+    }
+    send_progress(
+        progress,
+        &format!("已读取 {} 个工作表", ranges.len()),
+        LogType::Info,
+    )?;
+    Ok(ranges)
+}
+
+/// 每个工作表只保留表头和之后的 `max_rows` 行。同一个工作表配置了多次时按最靠后的表头行计算
+fn truncate_sheet_ranges(
+    ranges: &mut HashMap<String, Range<DataType>>,
+    sheet_configs: &[SheetConfig],
+    max_rows: usize,
+) {
+    for (name, range) in ranges.iter_mut() {
+        let (Some(start), Some(end)) = (range.start(), range.end()) else {
+            continue;
+        };
+        let header_row = sheet_configs
+            .iter()
+            .filter(|c| &c.name == name)
+            .map(|c| c.header_row)
+            .max()
+            .unwrap_or_default();
+        let last_row = (start.0 as usize).saturating_add(header_row + max_rows);
+        if last_row < end.0 as usize {
+            *range = range.range(start, (last_row as u32, end.1));
+        }
+    }
+}
+
+/// 读取工作表的公式 (formula_diagnostics 诊断用)，
+/// 不支持公式的格式或读取失败的工作表直接跳过
+fn cache_formula_ranges<RS, R>(
+    workbook: &mut R,
+    sheet_configs: &[SheetConfig],
+) -> HashMap<String, Range<String>>
+where
+    RS: Read + Seek,
+    R: Reader<RS>,
+{
+    let mut formulas = HashMap::new();
+    for sheet_config in sheet_configs {
+        if formulas.contains_key(&sheet_config.name) {
+            continue;
+        }
+        if let Some(Ok(range)) = workbook.worksheet_formula(&sheet_config.name) {
+            formulas.insert(sheet_config.name.clone(), range);
+        }
+    }
+    formulas
+}
+
+/// 查找单元格的公式。公式和值的 Range 起点可能不同，按绝对位置对应
+fn cell_formula<'a>(
+    formulas: &'a HashMap<String, Range<String>>,
+    sheet: &str,
+    range: &Range<DataType>,
+    row: usize,
+    col: usize,
+) -> Option<&'a str> {
+    let (start_row, start_col) = range.start()?;
+    formulas
+        .get(sheet)?
+        .get_value((start_row + row as u32, start_col + col as u32))
+        .map(String::as_str)
+        .filter(|f| !f.is_empty())
+}
+
+/// 按状态列过滤行: 状态不在允许列表中的行整行清空，
+/// 之后的读取都会当作空行跳过，行号保持不变
+fn filter_rows_by_status(
+    progress: &Progress,
+    sheet_configs: &[SheetConfig],
+    ranges: &mut HashMap<String, Range<DataType>>,
+    status_column: &str,
+    include_statuses: &[String],
+) -> Result<(), ExportError> {
+    let include: HashSet<String> = include_statuses
+        .iter()
+        .map(|s| s.trim().to_lowercase())
+        .collect();
+    let mut filtered = HashSet::new();
+    for sheet_config in sheet_configs {
+        if !filtered.insert(&sheet_config.name) {
+            continue;
+        }
+        let Some(range) = ranges.get_mut(&sheet_config.name) else {
+            continue;
+        };
+        let Some(header_row) = sheet_config.header(range)? else {
+            continue;
+        };
+        let Some(status_col) = header_row
+            .iter()
+            .position(|c| get_cell_string(c, ColumnType::Auto).trim() == status_column)
+        else {
+            continue;
+        };
+        let skipped: Vec<usize> = range
+            .rows()
+            .enumerate()
+            .skip(sheet_config.header_row + 1)
+            .filter(|(_, row)| row.iter().any(|c| *c != DataType::Empty))
+            .filter(|(_, row)| {
+                let status = row
+                    .get(status_col)
+                    .map(|c| get_cell_string(c, ColumnType::Auto))
+                    .unwrap_or_default();
+                !include.contains(&status.trim().to_lowercase())
+            })
+            .map(|(row_idx, _)| row_idx)
+            .collect();
+        if skipped.is_empty() {
+            continue;
+        }
+        let (start_row, start_col) = range.start().unwrap_or_default();
+        let width = range.width() as u32;
+        for row_idx in &skipped {
+            for col in 0..width {
+                range.set_value(
+                    (start_row + *row_idx as u32, start_col + col),
+                    DataType::Empty,
+                );
+            }
+        }
+        send_progress(
+            progress,
+            &format!(
+                "工作表 {} 跳过 {} 行 (状态不是 {})",
+                sheet_config.name,
+                skipped.len(),
+                include_statuses.join(" / ")
+            ),
+            LogType::Info,
+        )?;
+    }
+    Ok(())
+}
+
+/// 各语言并行处理时共享的只读上下文
+struct ExportContext<'a> {
+    progress: &'a Progress<'a>,
+    options: &'a ConvertOptions,
+    output_formats: &'a [OutputFormat],
+    sheet_configs: &'a [SheetConfig],
+    ranges: &'a HashMap<String, Range<DataType>>,
+    output_dir: &'a Path,
+    /// 全局 key 列索引
+    key_column: usize,
+    nesting: OutputNesting,
+    plural: Option<PluralFormat>,
+    rtl_marks: Option<RtlMarks>,
+    encoding: OutputEncoding,
+    /// 术语表工作表及其 sheet 配置
+    glossary: Option<(&'a Range<DataType>, &'a SheetConfig)>,
+    /// 已校验的输出文件名模式
+    filename_pattern: &'a str,
+    /// 增量导出时上一次导出的内容: 语言 -> 点分 key -> 值
+    snapshot: Option<&'a FlatLanguages>,
+    /// 工作表名 -> 公式，formula_diagnostics 关闭时为空
+    formulas: &'a HashMap<String, Range<String>>,
+    /// 以 `<key>_comment` 写入语言文件的译者说明
+    inline_comments: Option<&'a SheetComments>,
+    /// 说明列的全部内容，不论 comment_style，ARB 格式写成 description
+    comments: Option<&'a SheetComments>,
+    /// 只在内存中生成内容，不写任何文件
+    in_memory: bool,
+    /// root_wrapper 的各段路径，为空时不包装
+    root_wrapper: &'a [String],
+    placeholders: &'a PlaceholderSyntax,
+    total_steps: f32,
+    done_steps: AtomicUsize,
+}
+
+impl ExportContext<'_> {
+    fn done_fraction(&self) -> f32 {
+        self.done_steps.load(Ordering::SeqCst) as f32 / self.total_steps
+    }
+}
+
+/// 汇总提示中的行号示例，最多列出前 5 行
+fn row_sample(rows: &[usize]) -> String {
+    const SAMPLE: usize = 5;
+    let shown: Vec<String> = rows.iter().take(SAMPLE).map(|r| r.to_string()).collect();
+    if rows.len() > SAMPLE {
+        format!("行: {} 等", shown.join(", "))
+    } else {
+        format!("行: {}", shown.join(", "))
+    }
+}
+
+/// 解析每个语言的回退语言 (在 lang_configs 中的位置)。语言配置中声明的回退语言优先，
+/// 其次是 fallback_to_base 的基准语言；回退形成循环时报错
+fn resolve_fallback_parents(
+    lang_configs: &[LanguageConfig],
+    base: Option<usize>,
+) -> Result<Vec<Option<usize>>, String> {
+    let mut parents = Vec::with_capacity(lang_configs.len());
+    for (i, config) in lang_configs.iter().enumerate() {
+        let parent = match &config.fallback {
+            Some(code) => Some(
+                lang_configs
+                    .iter()
+                    .position(|c| &c.code == code)
+                    .ok_or_else(|| {
+                        format!(
+                            "语言 {} 的回退语言 {} 不在导出语言列表中",
+                            config.code, code
+                        )
+                    })?,
+            ),
+            None => base.filter(|b| *b != i),
+        };
+        parents.push(parent);
+    }
+    for start in 0..lang_configs.len() {
+        let mut chain = vec![start];
+        let mut current = start;
+        while let Some(parent) = parents[current] {
+            chain.push(parent);
+            if parent == start {
+                let codes: Vec<&str> = chain
+                    .iter()
+                    .map(|i| lang_configs[*i].code.as_str())
+                    .collect();
+                return Err(format!("语言回退形成循环: {}", codes.join(" -> ")));
+            }
+            if chain.len() > lang_configs.len() {
+                // 循环不经过 start，从循环中的语言开始检查时会报告
+                break;
+            }
+            current = parent;
+        }
+    }
+    Ok(parents)
+}
+
+/// 单个语言的处理结果
+struct LanguageOutput {
+    code: String,
+    sheet_data_map: SheetDataMap,
+    final_json: JsonValue,
+    files: Vec<PathBuf>,
+    report: ExportReport,
+    /// 使用回退语言的空值数量
+    fallback_count: usize,
+    /// 工作表名 -> key -> (提供值的语言, 回退的级数)，下一级回退时据此找到真正的来源
+    fallback_origins: HashMap<String, HashMap<String, (String, usize)>>,
+}
+
+/// 读取单个语言的所有工作表，合并后写入文件
+/// `parent` 为回退语言已处理完的结果，空值用其中的值代替 (其中的值可能又来自更上一级)；
+/// `internal` 的语言只作为回退来源，不写文件也不发送日志
+fn export_language(
+    ctx: &ExportContext,
+    lang_config: &LanguageConfig,
+    parent: Option<&LanguageOutput>,
+    internal: bool,
+) -> Result<LanguageOutput, ExportError> {
+    let muted = ctx.progress.muted();
+    let progress = if internal { &muted } else { ctx.progress };
+    send_progress_pct(
+        progress,
+        &format!("正在处理语言: {}", lang_config.code),
+        LogType::Info,
+        ctx.done_fraction(),
+    )?;
+
+    let mut report = ExportReport::default();
+    let mut sheet_data_map: SheetDataMap = IndexMap::new();
+    let rtl_marks = ctx.rtl_marks.filter(|_| lang_config.rtl);
+    let (empty_level, empty_label) = if ctx.options.fail_on_empty {
+        (IssueLevel::Error, "空值错误")
+    } else {
+        (IssueLevel::Warning, "空值警告")
+    };
+    let mut fallback_count = 0;
+    let mut fallback_origins: HashMap<String, HashMap<String, (String, usize)>> = HashMap::new();
+    // 工作表名 -> key -> 行号 (重复 key 取最后出现的行，与取值一致)
+    let mut key_rows: HashMap<String, HashMap<String, usize>> = HashMap::new();
+
+    for sheet_config in ctx.sheet_configs {
+        if progress.is_cancelled() {
+            return Err(ExportError::Cancelled);
+        }
+        ctx.done_steps.fetch_add(1, Ordering::SeqCst);
+        let Some(range) = ctx.ranges.get(&sheet_config.name) else {
+            continue;
+        };
+
+        let header_row = match sheet_config.header(range)? {
+            Some(h) => h,
+            None => continue,
+        };
+
+        let lang_col = match find_language_column(header_row, &lang_config.code) {
+            Some(c) => c,
+            None => continue,
+        };
+
+        let key_col = sheet_config.key_column.unwrap_or(ctx.key_column);
+        if key_col >= header_row.len() {
+            return Err(ExportError::Other(format!(
+                "工作表 {} 的 key 列 (第 {} 列) 超出范围, 该表只有 {} 列",
+                sheet_config.name,
+                key_col + 1,
+                header_row.len()
+            )));
+        }
+        let value_type = sheet_config.column_type(header_row, lang_col);
+        let transforms = sheet_config.transforms_for(&lang_config.code);
+
+        let mut temp: IndexMap<String, CellValue> = IndexMap::new();
+        // 非 verbose 模式下逐行的空值和回退只记录行号，读完工作表后汇总为一条提示
+        let mut empty_rows: Vec<usize> = Vec::new();
+        let mut fallback_rows: Vec<usize> = Vec::new();
+        // 提供回退值的语言 -> 数量
+        let mut fallback_sources: IndexMap<String, usize> = IndexMap::new();
+        let rows = key_rows.entry(sheet_config.name.clone()).or_default();
+
+        for (row_idx, row, key) in
+            sheet_keys(range, sheet_config, header_row, ctx.key_column, ctx.options)
+        {
+            let formula = cell_formula(ctx.formulas, &sheet_config.name, range, row_idx, lang_col);
+            let cell = row.get(lang_col);
+            let mut value = match cell {
+                Some(cell) => normalize_value(
+                    get_localized_cell_string(cell, value_type, lang_config),
+                    ctx.options,
+                ),
+                None => String::new(),
+            };
+            if let Some(serial) = cell.and_then(|c| out_of_range_date(c, value_type)) {
+                report.record(
+                    progress,
+                    Issue {
+                        category: IssueCategory::DateOutOfRange,
+                        sheet: Some(sheet_config.name.clone()),
+                        row: Some(row_idx + 1),
+                        language: Some(lang_config.code.clone()),
+                        key: Some(key.clone()),
+                        message: format!(
+                            "日期超出范围 Sheet: '{}' 行: {} 列: '{}' Key: '{}' 值: {} (按数字输出)",
+                            sheet_config.name,
+                            row_idx + 1,
+                            lang_config.code,
+                            key,
+                            format_number(serial)
+                        ),
+                        ..Default::default()
+                    },
+                )?;
+            }
+            if let Some(chars) = find_invisible_chars(&value, false) {
+                report.record(
+                    progress,
+                    Issue {
+                        category: IssueCategory::InvisibleChar,
+                        sheet: Some(sheet_config.name.clone()),
+                        row: Some(row_idx + 1),
+                        language: Some(lang_config.code.clone()),
+                        key: Some(key.clone()),
+                        message: format!(
+                            "值含有不可见字符 Sheet: '{}' 行: {} 列: '{}' Key: '{}' 字符: {}{}",
+                            sheet_config.name,
+                            row_idx + 1,
+                            lang_config.code,
+                            key,
+                            chars,
+                            if ctx.options.clean_invisible {
+                                " (已自动去除)"
+                            } else {
+                                ""
+                            }
+                        ),
+                        ..Default::default()
+                    },
+                )?;
+                if ctx.options.clean_invisible {
+                    value = clean_invisible_chars(&value, false);
+                }
+            }
+            // 转换在校验之前，校验和写出的都是转换后的值
+            if !transforms.is_empty() {
+                value = transforms.apply(&value, ctx.placeholders);
+            }
+            // 保留类型的值只影响写出的 JSON，下面的校验仍使用文本形式
+            let mut typed = cell
+                .filter(|_| ctx.options.preserve_types)
+                .and_then(|c| typed_cell_value(c, value_type));
+            // 错误值 (#REF! 等) 不写入输出，按空值处理
+            if let Some(DataType::Error(e)) = cell {
+                let err = ExportError::CellError {
+                    sheet: sheet_config.name.clone(),
+                    row: row_idx + 1,
+                    language: lang_config.code.clone(),
+                    key: key.clone(),
+                    error: format!("{}{}", e, formula_note(formula)),
+                };
+                if ctx.options.strict && !ctx.options.validate_only {
+                    return Err(err);
+                }
+                report.record(
+                    progress,
+                    Issue {
+                        level: if ctx.options.strict {
+                            IssueLevel::Error
+                        } else {
+                            IssueLevel::Warning
+                        },
+                        category: IssueCategory::CellError,
+                        sheet: Some(sheet_config.name.clone()),
+                        row: Some(row_idx + 1),
+                        language: Some(lang_config.code.clone()),
+                        key: Some(key.clone()),
+                        message: format!("{} (按空值处理)", err),
+                    },
+                )?;
+                value.clear();
+            }
+
+            let blank = is_blank(&value, ctx.options.treat_blank_as_empty);
+            let fallback = parent
+                .filter(|_| blank)
+                .and_then(|p| Some((p, p.sheet_data_map.get(&sheet_config.name)?.get(&key)?)))
+                .filter(|(_, v)| !v.is_empty());
+            if let Some((parent, fallback)) = fallback {
+                value = fallback.to_string();
+                typed = Some(fallback.clone()).filter(|v| !matches!(v, CellValue::Text(_)));
+                let (source, level) = match parent
+                    .fallback_origins
+                    .get(&sheet_config.name)
+                    .and_then(|o| o.get(&key))
+                {
+                    Some((source, level)) => (source.clone(), level + 1),
+                    None => (parent.code.clone(), 1),
+                };
+                fallback_count += 1;
+                fallback_rows.push(row_idx + 1);
+                *fallback_sources.entry(source.clone()).or_default() += 1;
+                if ctx.options.verbose {
+                    send_progress(
+                        progress,
+                        &format!(
+                            "使用回退语言 {} (第 {} 级) Sheet: '{}' 行: {} 列: '{}' Key: '{}'",
+                            source,
+                            level,
+                            sheet_config.name,
+                            row_idx + 1,
+                            lang_config.code,
+                            key
+                        ),
+                        LogType::Info,
+                    )?;
+                }
+                fallback_origins
+                    .entry(sheet_config.name.clone())
+                    .or_default()
+                    .insert(key.clone(), (source, level));
+            } else if blank {
+                let issue = Issue {
+                    level: empty_level,
+                    category: IssueCategory::EmptyValue,
+                    sheet: Some(sheet_config.name.clone()),
+                    row: Some(row_idx + 1),
+                    language: Some(lang_config.code.clone()),
+                    key: Some(key.clone()),
+                    message: format!(
+                        "{} Sheet: '{}' 行: {} 列: '{}' Key: '{}'{}{}",
+                        empty_label,
+                        sheet_config.name,
+                        row_idx + 1,
+                        lang_config.code,
+                        key,
+                        if value.is_empty() {
+                            ""
+                        } else {
+                            " (只有空白字符)"
+                        },
+                        formula_note(formula)
+                    ),
+                };
+                if ctx.options.verbose {
+                    report.record(progress, issue)?;
+                } else {
+                    empty_rows.push(row_idx + 1);
+                    report.record_silent(issue);
+                }
+            }
+
+            if let Err(reason) =
+                check_placeholders(&value, ctx.options.allow_single_braces, ctx.placeholders)
+            {
+                let err = ExportError::PlaceholderError {
+                    sheet: sheet_config.name.clone(),
+                    row: row_idx + 1,
+                    key: key.clone(),
+                    value: value.clone(),
+                    reason: reason + &formula_note(formula),
+                };
+                if !ctx.options.validate_only {
+                    return Err(err);
+                }
+                report.record(
+                    progress,
+                    Issue {
+                        level: IssueLevel::Error,
+                        category: IssueCategory::PlaceholderError,
+                        sheet: Some(sheet_config.name.clone()),
+                        row: Some(row_idx + 1),
+                        language: Some(lang_config.code.clone()),
+                        key: Some(key.clone()),
+                        message: err.to_string(),
+                    },
+                )?;
+            }
+
+            if let Some(max) = ctx.options.max_value_length {
+                let length = value.chars().count();
+                if length > max {
+                    let err = ExportError::ValueTooLong {
+                        sheet: sheet_config.name.clone(),
+                        row: row_idx + 1,
+                        language: lang_config.code.clone(),
+                        key: key.clone(),
+                        length,
+                        max,
+                        formula: formula.map(str::to_string),
+                    };
+                    if ctx.options.strict && !ctx.options.validate_only {
+                        return Err(err);
+                    }
+                    report.record(
+                        progress,
+                        Issue {
+                            level: if ctx.options.strict {
+                                IssueLevel::Error
+                            } else {
+                                IssueLevel::Warning
+                            },
+                            category: IssueCategory::ValueTooLong,
+                            sheet: Some(sheet_config.name.clone()),
+                            row: Some(row_idx + 1),
+                            language: Some(lang_config.code.clone()),
+                            key: Some(key.clone()),
+                            message: err.to_string(),
+                        },
+                    )?;
+                }
+            }
+
+            if ctx.options.omit_empty && is_blank(&value, ctx.options.treat_blank_as_empty) {
+                continue;
+            }
+
+            if temp.contains_key(&key) {
+                report.record(
+                    progress,
+                    Issue {
+                        category: IssueCategory::DuplicateKey,
+                        sheet: Some(sheet_config.name.clone()),
+                        row: Some(row_idx + 1),
+                        language: Some(lang_config.code.clone()),
+                        key: Some(key.clone()),
+                        message: format!(
+                            "重复 Key 警告 Sheet: '{}' 行: {} Key: '{}' (后出现的值会覆盖前面的值)",
+                            sheet_config.name,
+                            row_idx + 1,
+                            key
+                        ),
+                        ..Default::default()
+                    },
+                )?;
+            }
+
+            // 校验都针对表格中的原值，报告中的位置和长度不受控制符影响
+            let value = match rtl_marks {
+                Some(marks) if !value.is_empty() => {
+                    apply_rtl_marks(&value, marks, ctx.placeholders)
+                }
+                _ => value,
+            };
+
+            rows.insert(key.clone(), row_idx + 1);
+            temp.insert(key, typed.unwrap_or(CellValue::Text(value)));
+        }
+
+        if !empty_rows.is_empty() {
+            send_progress(
+                progress,
+                &format!(
+                    "{} Sheet: '{}' 列: '{}' 共 {} 个空值 ({})",
+                    empty_label,
+                    sheet_config.name,
+                    lang_config.code,
+                    empty_rows.len(),
+                    row_sample(&empty_rows)
+                ),
+                match empty_level {
+                    IssueLevel::Warning => LogType::Warning,
+                    IssueLevel::Error => LogType::Error,
+                },
+            )?;
+        }
+        if !fallback_rows.is_empty() && !ctx.options.verbose {
+            let sources: Vec<String> = fallback_sources
+                .iter()
+                .map(|(code, count)| format!("{} {} 个", code, count))
+                .collect();
+            send_progress(
+                progress,
+                &format!(
+                    "使用回退语言 Sheet: '{}' 列: '{}' 共 {} 个值, 来自 {} ({})",
+                    sheet_config.name,
+                    lang_config.code,
+                    fallback_rows.len(),
+                    sources.join(" / "),
+                    row_sample(&fallback_rows)
+                ),
+                LogType::Info,
+            )?;
+        }
+
+        // 每个工作表一条，只报数量，细节已在上面的汇总里
+        send_progress_pct(
+            progress,
+            &format!(
+                "工作表读取完成 Sheet: '{}' 语言: {} 共 {} 个 key",
+                sheet_config.name,
+                lang_config.code,
+                temp.len()
+            ),
+            LogType::Info,
+            ctx.done_fraction(),
+        )?;
+        sheet_data_map.insert(sheet_config.name.clone(), temp);
+    }
+
+    // 所有工作表读完后统一替换术语引用
+    if let Some((range, glossary_config)) = ctx.glossary {
+        let glossary = read_glossary(
+            range,
+            glossary_config,
+            &lang_config.code,
+            ctx.key_column,
+            ctx.options,
+        )?;
+        for (sheet, data) in sheet_data_map.iter_mut() {
+            for (key, value) in data.iter_mut() {
+                let CellValue::Text(value) = value else {
+                    continue;
+                };
+                if !GLOSSARY_REF_RE.is_match(value) {
+                    continue;
+                }
+                match resolve_glossary(value, &glossary, &mut Vec::new()) {
+                    Ok(resolved) => *value = resolved,
+                    Err(reason) => {
+                        let row = key_rows
+                            .get(sheet)
+                            .and_then(|r| r.get(key))
+                            .copied()
+                            .unwrap_or_default();
+                        let err = ExportError::GlossaryError {
+                            sheet: sheet.clone(),
+                            row,
+                            language: lang_config.code.clone(),
+                            key: key.clone(),
+                            reason,
+                        };
+                        if !ctx.options.validate_only {
+                            return Err(err);
+                        }
+                        report.record(
+                            progress,
+                            Issue {
+                                level: IssueLevel::Error,
+                                category: IssueCategory::GlossaryError,
+                                sheet: Some(sheet.clone()),
+                                row: Some(row),
+                                language: Some(lang_config.code.clone()),
+                                key: Some(key.clone()),
+                                message: err.to_string(),
+                            },
+                        )?;
+                    }
+                }
+            }
+        }
+    }
+
+    // 疑似复制粘贴: 同一语言中不同 key 的长值完全相同 (回退得到的值不算)
+    if let Some(min_length) = ctx.options.duplicate_value_min_length {
+        let mut groups: IndexMap<&str, Vec<String>> = IndexMap::new();
+        for sheet_config in ctx.sheet_configs {
+            let Some(data) = sheet_data_map.get(&sheet_config.name) else {
+                continue;
+            };
+            let prefix = sheet_config.key_prefix();
+            let origins = fallback_origins.get(&sheet_config.name);
+            let rows = key_rows.get(&sheet_config.name);
+            for (key, value) in data {
+                let CellValue::Text(value) = value else {
+                    continue;
+                };
+                let value = value.trim();
+                if value.chars().count() < min_length
+                    || origins.is_some_and(|o| o.contains_key(key))
+                    || ctx
+                        .options
+                        .duplicate_value_allowlist
+                        .iter()
+                        .any(|v| v.trim() == value)
+                {
+                    continue;
+                }
+                let row = rows.and_then(|r| r.get(key)).copied().unwrap_or_default();
+                groups.entry(value).or_default().push(format!(
+                    "{}{} ({}:{})",
+                    prefix,
+                    key.replace("\\.", "."),
+                    sheet_config.name,
+                    row
+                ));
+            }
+        }
+        for (value, keys) in groups.iter().filter(|(_, keys)| keys.len() > 1) {
+            report.record(
+                progress,
+                Issue {
+                    level: IssueLevel::Warning,
+                    category: IssueCategory::DuplicateValue,
+                    language: Some(lang_config.code.clone()),
+                    message: format!(
+                        "疑似复制粘贴 语言: {} 有 {} 个 key 的值相同: {} 值: {:?}",
+                        lang_config.code,
+                        keys.len(),
+                        keys.join(", "),
+                        value
+                    ),
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+
+    // 合并 sheet 数据，同时按点分 key 记录每个 key 的来源行和说明
+    let mut final_json = JsonValue::new_object();
+    let mut notes = KeyNotes::default();
+    for sheet_config in ctx.sheet_configs {
+        let Some(temp) = sheet_data_map.get(&sheet_config.name) else {
+            continue;
+        };
+        let is_root = sheet_config.sheet_type.as_deref() == Some("root");
+        let expand = ctx.nesting == OutputNesting::Nested
+            || sheet_config.sheet_type.as_deref() == Some("nested");
+        let prefix = sheet_config.key_prefix();
+        let mut sheet_obj = JsonValue::new_object();
+        // 嵌套冲突: 校验模式下记录后继续，否则中止导出
+        let mut conflict = |key: String, reason: String| -> Result<(), ExportError> {
+            let err = ExportError::NestedKeyConflict {
+                sheet: sheet_config.name.clone(),
+                key: key.clone(),
+                reason,
+            };
+            if !ctx.options.validate_only {
+                return Err(err);
+            }
+            report.record(
+                progress,
+                Issue {
+                    level: IssueLevel::Error,
+                    category: IssueCategory::NestedKeyConflict,
+                    sheet: Some(sheet_config.name.clone()),
+                    language: Some(lang_config.code.clone()),
+                    key: Some(key),
+                    message: err.to_string(),
+                    ..Default::default()
+                },
+            )?;
+            Ok(())
+        };
+        let comments = ctx.inline_comments.and_then(|c| c.get(&sheet_config.name));
+        // 说明写在值后面，作为同级的 `<key>_comment`
+        let entries = temp.iter().flat_map(|(k, v)| {
+            let comment = comments
+                .and_then(|c| c.get(k))
+                .map(|c| (format!("{}_comment", k), JsonValue::from(c.as_str())));
+            std::iter::once((k.clone(), v.to_json())).chain(comment)
+        });
+        for (k, value) in entries {
+            if let Some(row) = key_rows.get(&sheet_config.name).and_then(|r| r.get(&k)) {
+                notes.sources.insert(
+                    format!("{}{}", prefix, k.replace("\\.", ".")),
+                    format!("{}:{}", sheet_config.name, row),
+                );
+            }
+
+            let target = if is_root {
+                &mut final_json
+            } else {
+                &mut sheet_obj
+            };
+            if !expand {
+                target[k.as_str()] = value;
+            } else if let Err(reason) = insert_nested_key(target, &k, value) {
+                conflict(k, reason)?;
+            }
+        }
+        if let Some(sheet_comments) = ctx.comments.and_then(|c| c.get(&sheet_config.name)) {
+            for (k, comment) in sheet_comments.iter().filter(|(k, _)| temp.contains_key(*k)) {
+                notes.descriptions.insert(
+                    format!("{}{}", prefix, k.replace("\\.", ".")),
+                    comment.clone(),
+                );
+            }
+        }
+        // 非 root 工作表整体放在 json_path 下，路径中的点同样展开为嵌套对象
+        if !is_root {
+            if let Err(reason) =
+                insert_nested_key(&mut final_json, &sheet_config.json_path, sheet_obj)
+            {
+                conflict(sheet_config.json_path.clone(), reason)?;
+            }
+        }
+    }
+    // 复数 key 归组，并检查每组是否包含该语言要求的复数类别
+    let mut plural_groups = IndexMap::new();
+    if let Some(plural_format) = ctx.plural {
+        final_json = group_plurals(&final_json, plural_format, "", &mut plural_groups);
+        let required = required_plural_categories(&lang_config.code);
+        for (key, categories) in &plural_groups {
+            let missing: Vec<&str> = required
+                .iter()
+                .copied()
+                .filter(|c| !categories.contains_key(*c))
+                .collect();
+            if missing.is_empty() {
+                continue;
+            }
+            report.record(
+                progress,
+                Issue {
+                    category: IssueCategory::MissingPlural,
+                    language: Some(lang_config.code.clone()),
+                    key: Some(key.clone()),
+                    message: format!(
+                        "缺少复数形式 语言: '{}' Key: '{}' 缺少: {}",
+                        lang_config.code,
+                        key,
+                        missing.join(", ")
+                    ),
+                    ..Default::default()
+                },
+            )?;
+        }
+    }
+
+    // 按工作表拆分: (文件名, 内容, 该部分在合并结果中的路径)
+    let mut parts: Vec<(String, JsonValue, String)> = Vec::new();
+    if ctx.options.split_by_sheet {
+        let sheets: Vec<&SheetConfig> = ctx
+            .sheet_configs
+            .iter()
+            .filter(|c| c.sheet_type.as_deref() != Some("root"))
+            .filter(|c| sheet_data_map.contains_key(&c.name))
+            .collect();
+        // 各工作表路径的第一段之外的顶层 key 属于 root 工作表
+        let top_keys: HashSet<String> = sheets
+            .iter()
+            .filter_map(|c| split_dotted_key(&c.json_path).into_iter().next())
+            .collect();
+        let mut common = JsonValue::new_object();
+        for (k, v) in final_json.entries() {
+            if !top_keys.contains(k) {
+                common[k] = v.clone();
+            }
+        }
+        if !common.is_empty() {
+            let root_file_name = ctx
+                .options
+                .root_file_name
+                .clone()
+                .filter(|n| !n.trim().is_empty())
+                .unwrap_or_else(|| "common".to_string());
+            parts.push((root_file_name, common, String::new()));
+        }
+        for sheet_config in sheets {
+            let value = split_dotted_key(&sheet_config.json_path)
+                .iter()
+                .fold(&final_json, |v, part| &v[part.as_str()]);
+            parts.push((
+                sheet_config.name.clone(),
+                value.clone(),
+                sheet_config.json_path.clone(),
+            ));
+        }
+    }
+
+    let mut final_json = finish_output(final_json, ctx.nesting, ctx.options.sort_keys);
+    // root_wrapper 在快照比较之后才包装，平铺格式的 key 和说明都带上这个前缀
+    let wrapper_prefix = if ctx.root_wrapper.is_empty() {
+        String::new()
+    } else {
+        format!("{}.", ctx.root_wrapper.join("."))
+    };
+    // 增量导出后为空是正常的，只检查完整的结果；按工作表数据判断，不算 inline 说明生成的 `<key>_comment`
+    let empty_language = sheet_data_map
+        .values()
+        .flat_map(|data| data.values())
+        .all(CellValue::is_empty);
+    if empty_language {
+        report.record(
+            progress,
+            Issue {
+                category: IssueCategory::EmptyLanguage,
+                language: Some(lang_config.code.clone()),
+                message: format!(
+                    "语言 {} 没有任何有值的 key, 可能是整列为空或各工作表都没有该语言列{}",
+                    lang_config.code,
+                    if ctx.options.skip_empty_languages {
+                        " (已跳过, 不写入语言文件)"
+                    } else {
+                        ""
+                    }
+                ),
+                ..Default::default()
+            },
+        )?;
+    }
+    // 增量导出: 只保留相对快照新增或修改的 key
+    let snapshot = ctx
+        .snapshot
+        .map(|s| s.get(&lang_config.code).unwrap_or(&EMPTY_FLAT));
+    if let Some(snapshot) = snapshot {
+        let changed = retain_changed(&mut final_json, "", snapshot);
+        send_progress(
+            progress,
+            &format!(
+                "语言 {} 相对快照有 {} 个 key 新增或修改",
+                lang_config.code, changed
+            ),
+            LogType::Info,
+        )?;
+    }
+    let parts: Vec<_> = parts
+        .into_iter()
+        .map(|(name, value, path)| {
+            let mut value = finish_output(value, ctx.nesting, ctx.options.sort_keys);
+            if let Some(snapshot) = snapshot {
+                retain_changed(&mut value, &path, snapshot);
+            }
+            let prefix = if path.is_empty() {
+                String::new()
+            } else {
+                format!("{}.", path.replace("\\.", "."))
+            };
+            // 来源和说明按合并后的完整 key 记录，这里换成该文件内的 key
+            let part_notes = notes.strip_prefix(&prefix);
+            if ctx.root_wrapper.is_empty() {
+                (name, value, part_notes)
+            } else {
+                let wrapped = wrap_root(value, ctx.root_wrapper);
+                (name, wrapped, part_notes.with_prefix(&wrapper_prefix))
+            }
+        })
+        .collect();
+    if !ctx.root_wrapper.is_empty() {
+        final_json = wrap_root(final_json, ctx.root_wrapper);
+        notes = notes.with_prefix(&wrapper_prefix);
+        plural_groups = plural_groups
+            .into_iter()
+            .map(|(k, v)| (format!("{}{}", wrapper_prefix, k), v))
+            .collect();
+    }
+
+    let mut files = vec![];
+    let skip_files = empty_language && ctx.options.skip_empty_languages;
+    if !internal && !ctx.options.validate_only && !ctx.in_memory && !skip_files {
+        // 写入文件，每个文件生成后立即写出，不同时保留多个序列化结果
+        // combined 时 JSON 只写合并后的 all.json
+        let formats = ctx
+            .output_formats
+            .iter()
+            .filter(|f| !f.is_combined())
+            .filter(|f| !(ctx.options.combined && **f == OutputFormat::Json));
+        for format in formats {
+            if ctx.options.split_by_sheet && format.supports_custom_path() {
+                for (name, value, notes) in &parts {
+                    let path = expand_filename_pattern(
+                        ctx.filename_pattern,
+                        &lang_config.code,
+                        Some(name),
+                        format.extension(),
+                    );
+                    files.push(write_output(
+                        ctx,
+                        path,
+                        value,
+                        *format,
+                        &lang_config.code,
+                        notes,
+                    )?);
+                }
+            } else {
+                let path = if format.supports_custom_path() {
+                    expand_filename_pattern(
+                        ctx.filename_pattern,
+                        &lang_config.code,
+                        None,
+                        format.extension(),
+                    )
+                } else {
+                    PathBuf::from(format.file_path(&lang_config.code))
+                };
+                files.push(write_output(
+                    ctx,
+                    path,
+                    &final_json,
+                    *format,
+                    &lang_config.code,
+                    &notes,
+                )?);
+            }
+            if *format == OutputFormat::Ios && !plural_groups.is_empty() {
+                let path = PathBuf::from(format!(
+                    "{}.lproj/Localizable.stringsdict",
+                    lang_config.code
+                ));
+                files.push(write_output_text(
+                    ctx,
+                    path,
+                    &to_ios_stringsdict(&plural_groups),
+                )?);
+            }
+        }
+    }
+
+    if fallback_count > 0 {
+        send_progress(
+            progress,
+            &format!(
+                "语言 {} 有 {} 个空值使用了回退语言的值",
+                lang_config.code, fallback_count
+            ),
+            LogType::Info,
+        )?;
+    }
+
+    Ok(LanguageOutput {
+        code: lang_config.code.clone(),
+        sheet_data_map,
+        final_json,
+        files,
+        report,
+        fallback_count,
+        fallback_origins,
+    })
+}
+
+/// 低内存模式: 写出后只保留跨语言校验需要的信息
+fn compact_language_output(ctx: &ExportContext, mut output: LanguageOutput) -> LanguageOutput {
+    for temp in output.sheet_data_map.values_mut() {
+        for value in temp.values_mut() {
+            if let CellValue::Text(text) = value {
+                *text = placeholder_skeleton(text, ctx.placeholders);
+            }
+        }
+    }
+    // 合并输出和只返回内容时需要完整的值，schema 只需要结构
+    if ctx.output_formats.contains(&OutputFormat::CsvCombined)
+        || ctx.options.combined
+        || ctx.in_memory
+    {
+        return output;
+    }
+    // schema 和模板都只用到结构
+    if ctx.options.emit_schema || ctx.options.emit_template {
+        skeletonize_json(&mut output.final_json, ctx.placeholders);
+    } else {
+        output.final_json = JsonValue::Null;
+    }
+    output
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+enum CombinedShape {
+    /// `{ "key": { "en": "...", "zh": "..." } }`
+    ByKey,
+    /// `{ "en": {...}, "zh": {...} }`
+    ByLanguage,
+}
+
+fn parse_combined_shape(value: Option<&str>) -> Result<CombinedShape, String> {
+    match value.map(|v| v.trim().to_lowercase()).as_deref() {
+        None | Some("") | Some("by_key") => Ok(CombinedShape::ByKey),
+        Some("by_language") => Ok(CombinedShape::ByLanguage),
+        Some(other) => Err(format!("不支持的合并结构: {}", other)),
+    }
+}
+
+/// 所有语言合并为一个 JSON
+fn combine_languages(languages: &[(String, JsonValue)], shape: CombinedShape) -> JsonValue {
+    match shape {
+        CombinedShape::ByLanguage => {
+            let mut combined = JsonValue::new_object();
+            for (code, value) in languages {
+                combined[code.as_str()] = value.clone();
+            }
+            combined
+        }
+        CombinedShape::ByKey => {
+            let values: Vec<(&str, &JsonValue)> =
+                languages.iter().map(|(c, v)| (c.as_str(), v)).collect();
+            combine_by_key(&values)
+        }
+    }
+}
+
+/// 同一位置上各语言都是对象时按 key 逐层合并 (key 顺序按首次出现的顺序)，
+/// 否则为叶子，写成 语言 -> 值；缺少该位置的语言不写入
+fn combine_by_key(values: &[(&str, &JsonValue)]) -> JsonValue {
+    if !values.iter().all(|(_, v)| v.is_object()) {
+        let mut leaf = JsonValue::new_object();
+        for (code, value) in values {
+            leaf[*code] = (*value).clone();
+        }
+        return leaf;
+    }
+    let mut keys: IndexMap<&str, Vec<(&str, &JsonValue)>> = IndexMap::new();
+    for (code, value) in values {
+        for (key, child) in value.entries() {
+            keys.entry(key).or_default().push((code, child));
+        }
+    }
+    let mut combined = JsonValue::new_object();
+    for (key, children) in &keys {
+        combined[*key] = combine_by_key(children);
+    }
+    combined
+}
+
+/// 保留结构，字符串值替换为占位符骨架 (生成 schema 只需要结构和占位符)
+fn skeletonize_json(value: &mut JsonValue, syntax: &PlaceholderSyntax) {
+    match value {
+        JsonValue::Object(obj) => {
+            for (_, child) in obj.iter_mut() {
+                skeletonize_json(child, syntax);
+            }
+        }
+        JsonValue::Array(items) => items
+            .iter_mut()
+            .for_each(|item| skeletonize_json(item, syntax)),
+        JsonValue::String(_) | JsonValue::Short(_) => {
+            *value = placeholder_skeleton(value.as_str().unwrap_or_default(), syntax).into();
+        }
+        _ => {}
+    }
+}
+
+/// 保留结构和 key 的顺序，所有值替换为空字符串 (翻译模板)
+fn blank_json_values(value: &JsonValue) -> JsonValue {
+    match value {
+        JsonValue::Object(obj) => {
+            let mut out = JsonValue::new_object();
+            for (key, child) in obj.iter() {
+                out[key] = blank_json_values(child);
+            }
+            out
+        }
+        JsonValue::Array(items) => JsonValue::Array(items.iter().map(blank_json_values).collect()),
+        _ => "".into(),
+    }
+}
+
+/// 根据各语言合并后的结果生成 JSON Schema (draft-07)。
+/// `patterns` 不为 None 时，字符串节点加上必须包含共有占位符 (按该写法) 的 pattern
+fn build_json_schema(
+    final_jsons: &[(String, JsonValue)],
+    patterns: Option<&PlaceholderSyntax>,
+) -> serde_json::Value {
+    let values: Vec<&JsonValue> = final_jsons.iter().map(|(_, v)| v).collect();
+    let mut schema = json_schema_node(&values, patterns);
+    if let Some(obj) = schema.as_object_mut() {
+        obj.insert(
+            "$schema".to_string(),
+            json!("http://json-schema.org/draft-07/schema#"),
+        );
+    }
+    schema
+}
+
+/// 同一位置上各语言的值合并为一个 schema 节点，缺少该位置的语言不参与
+fn json_schema_node(
+    values: &[&JsonValue],
+    patterns: Option<&PlaceholderSyntax>,
+) -> serde_json::Value {
+    let Some(first) = values.first() else {
+        return json!({});
+    };
+    match first {
+        JsonValue::Object(_) => {
+            let mut keys: IndexMap<&str, Vec<&JsonValue>> = IndexMap::new();
+            for value in values {
+                for (key, child) in value.entries() {
+                    keys.entry(key).or_default().push(child);
+                }
+            }
+            let properties: serde_json::Map<_, _> = keys
+                .iter()
+                .map(|(key, children)| (key.to_string(), json_schema_node(children, patterns)))
+                .collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "additionalProperties": false,
+            })
+        }
+        JsonValue::Array(_) => {
+            let items: Vec<&JsonValue> = values.iter().flat_map(|v| v.members()).collect();
+            json!({ "type": "array", "items": json_schema_node(&items, patterns) })
+        }
+        JsonValue::String(_) | JsonValue::Short(_) => {
+            let mut node = json!({ "type": "string" });
+            let Some(syntax) = patterns else {
+                return node;
+            };
+            // 占位符取所有非空值共有的部分，避免导出的文件本身通不过校验
+            let common = values
+                .iter()
+                .filter_map(|v| v.as_str())
+                .filter(|v| !v.is_empty())
+                .map(|v| extract_placeholder_names(v, syntax))
+                .reduce(|a, b| a.intersection(&b).cloned().collect())
+                .unwrap_or_default();
+            if !common.is_empty() {
+                let lookaheads: String = common
+                    .iter()
+                    .map(|name| format!(r"(?=[\s\S]*{})", regex::escape(&syntax.wrap(name))))
+                    .collect();
+                node["pattern"] = json!(format!("^{}", lookaheads));
+            }
+            node
+        }
+        JsonValue::Number(_) => json!({ "type": "number" }),
+        JsonValue::Boolean(_) => json!({ "type": "boolean" }),
+        JsonValue::Null => json!({ "type": "null" }),
+    }
+}
+
+/// 写入单个语言的一个输出文件，返回完整路径。
+/// UTF-8 的 JSON 直接流式写入文件，不生成完整的字符串
+fn write_output(
+    ctx: &ExportContext,
+    relative_path: PathBuf,
+    value: &JsonValue,
+    format: OutputFormat,
+    code: &str,
+    notes: &KeyNotes,
+) -> Result<PathBuf, ExportError> {
+    if format != OutputFormat::Json || ctx.encoding.encoding != UTF_8 {
+        let content = serialize_output(value, format, code, notes, ctx.options, ctx.placeholders)?;
+        return write_output_text(ctx, relative_path, &content);
+    }
+
+    let output_path = prepare_output_path(ctx, relative_path)?;
+    let write = || -> std::io::Result<()> {
+        let mut writer = std::io::BufWriter::new(File::create(&output_path)?);
+        if ctx.encoding.bom {
+            writer.write_all("\u{FEFF}".as_bytes())?;
+        }
+        match ctx.options.indent {
+            0 => value.write(&mut writer)?,
+            indent => value.write_pretty(&mut writer, indent)?,
+        }
+        writer.flush()
+    };
+    write().map_err(|e| format!("{}: {}", output_path.display(), e))?;
+    report_output_written(ctx, output_path)
+}
+
+/// 按选项的编码写入文本内容，返回完整路径
+fn write_output_text(
+    ctx: &ExportContext,
+    relative_path: PathBuf,
+    content: &str,
+) -> Result<PathBuf, ExportError> {
+    let output_path = prepare_output_path(ctx, relative_path)?;
+    let bytes = ctx
+        .encoding
+        .encode(content)
+        .map_err(|e| format!("{}: {}", output_path.display(), e))?;
+    fs::write(&output_path, bytes).map_err(|e| e.to_string())?;
+    report_output_written(ctx, output_path)
+}
+
+/// 转为导出文件夹中的完整路径，并创建所在目录
+fn prepare_output_path(ctx: &ExportContext, relative_path: PathBuf) -> Result<PathBuf, String> {
+    let output_path = ctx.output_dir.join(relative_path);
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    Ok(output_path)
+}
+
+fn report_output_written(
+    ctx: &ExportContext,
+    output_path: PathBuf,
+) -> Result<PathBuf, ExportError> {
+    send_progress_pct(
+        ctx.progress,
+        &format!("✅ 已导出语言文件: {}", output_path.display()),
+        LogType::Success,
+        ctx.done_fraction(),
+    )?;
+    Ok(output_path)
+}
+
+#[tauri::command]
+fn cancel_export(state: State<'_, ExportState>) {
+    state.cancelled.store(true, Ordering::SeqCst);
+}
+
+/// 监听 Excel 文件，文件变化后 (防抖) 使用同样的选项自动重新导出
+#[tauri::command]
+fn watch_excel(
+    app: AppHandle,
+    state: State<'_, WatchState>,
+    path: String,
+    options: Option<ConvertOptions>,
+) -> Result<(), ExportError> {
+    use notify::Watcher;
+
+    let file_path = PathBuf::from(&path);
+    if !file_path.is_file() {
+        return Err(ExportError::FileNotFound { path });
+    }
+    // 监听所在目录而不是文件本身: Excel 等程序保存时会先写临时文件再替换原文件
+    let watch_dir = file_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .to_path_buf();
+    let file_name = file_path.file_name().map(|n| n.to_os_string());
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher =
+        notify::recommended_watcher(tx).map_err(|e| format!("创建文件监听失败: {}", e))?;
+    watcher
+        .watch(&watch_dir, notify::RecursiveMode::NonRecursive)
+        .map_err(|e| format!("监听目录失败: {}", e))?;
+    // 替换掉之前的监听
+    *state.watcher.lock().unwrap() = Some(watcher);
+    send_progress(
+        &app_progress(&app),
+        &format!("开始监听文件: {}", path),
+        LogType::Info,
+    )?;
+
+    std::thread::spawn(move || {
+        // 只关心目标文件的变化，导出结果写在同一目录下也不会再次触发
+        let is_target = |res: &notify::Result<notify::Event>| {
+            res.as_ref().is_ok_and(|event| {
+                !event.kind.is_access()
+                    && event
+                        .paths
+                        .iter()
+                        .any(|p| p.file_name() == file_name.as_deref())
+            })
+        };
+        while let Ok(res) = rx.recv() {
+            if !is_target(&res) {
+                continue;
+            }
+            loop {
+                match rx.recv_timeout(WATCH_DEBOUNCE) {
+                    Ok(_) => continue,
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            let _ = send_progress(
+                &app_progress(&app),
+                "检测到文件变化，重新导出",
+                LogType::Info,
+            );
+            // 导出失败的原因已经通过进度事件报告，这里继续监听
+            let _ = tauri::async_runtime::block_on(convert_excel_to_json(
+                app.clone(),
+                app.state::<ExportState>(),
+                path.clone(),
+                options.clone(),
+            ));
+        }
+    });
+    Ok(())
+}
+
+#[tauri::command]
+fn stop_watch(app: AppHandle, state: State<'_, WatchState>) -> Result<(), ExportError> {
+    if state.watcher.lock().unwrap().take().is_some() {
+        send_progress(&app_progress(&app), "已停止监听", LogType::Info)?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+async fn convert_excel_to_json(
+    app: AppHandle,
+    state: State<'_, ExportState>,
+    path: String,
+    options: Option<ConvertOptions>,
+) -> Result<String, ExportError> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    let progress = app_progress(&app).with_cancel_flag(&state.cancelled);
+    convert_workbook(Path::new(&path), options.unwrap_or_default(), &progress)
+        .map(|report| report.message)
+}
+
+/// 依次转换多个工作簿，每个文件单独导出，单个文件失败时继续处理下一个
+#[tauri::command]
+async fn convert_excel_batch(
+    app: AppHandle,
+    state: State<'_, ExportState>,
+    paths: Vec<String>,
+    options: Option<ConvertOptions>,
+) -> Result<String, ExportError> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    let progress = app_progress(&app).with_cancel_flag(&state.cancelled);
+    let paths: Vec<PathBuf> = paths.iter().map(PathBuf::from).collect();
+    convert_batch(&paths, &options.unwrap_or_default(), &progress).map(|report| report.message)
+}
+
+/// 导出前的快速检查: 只读取配置，返回发现的问题
+#[tauri::command]
+async fn validate_config(
+    path: String,
+    options: Option<ConvertOptions>,
+) -> Result<Vec<Issue>, ExportError> {
+    validate_workbook_config(Path::new(&path), &options.unwrap_or_default())
+}
+
+/// 列出工作簿中的工作表及其表头，供前端配置导出
+#[tauri::command]
+async fn inspect_workbook(
+    path: String,
+    options: Option<ConvertOptions>,
+) -> Result<Vec<SheetInfo>, ExportError> {
+    inspect_workbook_sheets(Path::new(&path), &options.unwrap_or_default())
+}
+
+/// 转换但不写文件，返回各语言的 JSON 内容，用于预览
+#[tauri::command]
+async fn preview_excel_json(
+    app: AppHandle,
+    state: State<'_, ExportState>,
+    path: String,
+    options: Option<ConvertOptions>,
+) -> Result<Vec<(String, String)>, ExportError> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    let progress = app_progress(&app).with_cancel_flag(&state.cancelled);
+    let mut outputs = Vec::new();
+    run_conversion(
+        Path::new(&path),
+        options.unwrap_or_default(),
+        &progress,
+        Some(&mut outputs),
+        None,
+    )?;
+    Ok(outputs)
+}
+
+/// 快速预览: 只转换每个工作表的前 `max_rows` 个数据行，返回各语言的 JSON 内容，不写文件
+#[tauri::command]
+async fn preview_conversion(
+    app: AppHandle,
+    state: State<'_, ExportState>,
+    path: String,
+    max_rows: usize,
+    options: Option<ConvertOptions>,
+) -> Result<Vec<(String, String)>, ExportError> {
+    state.cancelled.store(false, Ordering::SeqCst);
+    let progress = app_progress(&app).with_cancel_flag(&state.cancelled);
+    let mut outputs = Vec::new();
+    run_conversion(
+        Path::new(&path),
+        options.unwrap_or_default(),
+        &progress,
+        Some(&mut outputs),
+        Some(max_rows),
+    )?;
+    Ok(outputs)
+}
+
+/// sheet 配置中可用的工作表类型，不填为普通工作表 (ignore 的配置行在解析时已跳过)
+const SHEET_TYPES: [&str; 2] = ["root", "nested"];
+
+/// 只检查工作簿的配置，不读取数据行: 语言列表不为空且回退关系有效，配置的工作表都存在、类型有效。
+/// 配置中的错误和解析配置时的警告都作为问题返回，工作簿或配置文件无法打开时返回 Err
+pub fn validate_workbook_config(
+    path: &Path,
+    options: &ConvertOptions,
+) -> Result<Vec<Issue>, ExportError> {
+    if !path.exists() {
+        return Err(ExportError::FileNotFound {
+            path: path.display().to_string(),
+        });
+    }
+    let mut workbook = open_workbook_by_extension(path, options.password.as_deref())?;
+    let mut config = match options
+        .config_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+    {
+        Some(file) => load_config_file(Path::new(file))?,
+        None => Config::default(),
+    };
+    let lang_config_sheet = options
+        .lang_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_LANG_CONFIG_SHEET);
+    let sheet_config_sheet = options
+        .sheet_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_SHEET_CONFIG_SHEET);
+    let sheet_names = workbook.sheet_names().to_vec();
+
+    // 解析配置时以日志发出的警告 (如列类型声明无效) 也算作问题
+    let warnings = Mutex::new(Vec::new());
+    let progress = Progress::new(|event| {
+        if let ProgressEvent::Log {
+            message,
+            log_type: LogType::Warning | LogType::Error,
+            ..
+        } = event
+        {
+            let message = message.trim_start_matches("⚠️").trim().to_string();
+            warnings.lock().unwrap().push(message);
+        }
+        Ok(())
+    });
+    let error = |message: String| Issue {
+        level: IssueLevel::Error,
+        message,
+        ..Default::default()
+    };
+    let mut issues = Vec::new();
+
+    let languages = match config.languages.take() {
+        Some(entries) => Some(
+            entries
+                .into_iter()
+                .filter(|e| !e.code.trim().is_empty())
+                .map(parse_language_config)
+                .collect::<Result<Vec<_>, _>>(),
+        ),
+        // 导出时从表头推断语言，这里没有可检查的配置
+        None if options.auto_languages => None,
+        None if !sheet_names.iter().any(|n| n == lang_config_sheet) => {
+            issues.push(Issue {
+                message: format!(
+                    "没有语言配置表 {}, 导出时会从工作表表头推断语言",
+                    lang_config_sheet
+                ),
+                ..Default::default()
+            });
+            None
+        }
+        None => Some(read_language_configs_from_excel(
+            &mut workbook,
+            lang_config_sheet,
+        )),
+    };
+    match languages {
+        Some(Ok(languages)) if languages.is_empty() => {
+            issues.push(error("语言配置为空, 没有要导出的语言".to_string()))
+        }
+        Some(Ok(languages)) => {
+            if let Err(msg) = resolve_fallback_parents(&languages, None) {
+                issues.push(error(msg));
+            }
+        }
+        Some(Err(err)) => issues.push(error(err.to_string())),
+        None => {}
+    }
+
+    let sheets = match config.sheets.take() {
+        Some(entries) => entries
+            .into_iter()
+            .map(|entry| parse_sheet_config(&progress, entry))
+            .collect::<Result<Vec<_>, _>>()
+            .map(|configs| configs.into_iter().flatten().collect()),
+        None => read_sheet_configs_from_excel(&progress, &mut workbook, sheet_config_sheet),
+    };
+    match sheets {
+        Ok(sheets) if sheets.is_empty() => {
+            issues.push(error("sheet 配置为空, 没有要导出的工作表".to_string()))
+        }
+        Ok(sheets) => {
+            for sheet in &sheets {
+                if !sheet_names.contains(&sheet.name) {
+                    issues.push(Issue {
+                        sheet: Some(sheet.name.clone()),
+                        ..error(format!(
+                            "配置的工作表不存在: {} (现有工作表: {})",
+                            sheet.name,
+                            sheet_names.join(", ")
+                        ))
+                    });
+                }
+                if let Some(sheet_type) = sheet
+                    .sheet_type
+                    .as_deref()
+                    .filter(|t| !SHEET_TYPES.contains(t))
+                {
+                    issues.push(Issue {
+                        sheet: Some(sheet.name.clone()),
+                        ..error(format!(
+                            "工作表 {} 的类型 '{}' 无效, 可选: root / nested / ignore, 不填为普通工作表",
+                            sheet.name, sheet_type
+                        ))
+                    });
+                }
+            }
+        }
+        Err(err) => issues.push(error(err.to_string())),
+    }
+
+    drop(progress);
+    issues.extend(
+        warnings
+            .into_inner()
+            .unwrap()
+            .into_iter()
+            .map(|message| Issue {
+                message,
+                ..Default::default()
+            }),
+    );
+    Ok(issues)
+}
+
+/// 工作表在导出中的作用，按工作表名和内容推断
+#[derive(Serialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum SheetRole {
+    LanguageConfig,
+    SheetConfig,
+    Data,
+    Empty,
+}
+
+/// 工作簿中一个工作表的概况，供前端选择和配置要导出的工作表
+#[derive(Serialize, Clone, Debug)]
+pub struct SheetInfo {
+    pub name: String,
+    /// 首行各列的文本，末尾的空列已去掉
+    pub headers: Vec<String>,
+    /// 使用区域的行数，包含表头
+    pub row_count: usize,
+    pub role: SheetRole,
+}
+
+/// 列出工作簿中的全部工作表及其表头、行数和推断出的作用，不读取配置。
+/// 名称与语言配置表、sheet 配置表 (可通过选项修改) 相同的为配置表，其余有内容的为数据表
+pub fn inspect_workbook_sheets(
+    path: &Path,
+    options: &ConvertOptions,
+) -> Result<Vec<SheetInfo>, ExportError> {
+    if !path.exists() {
+        return Err(ExportError::FileNotFound {
+            path: path.display().to_string(),
+        });
+    }
+    let mut workbook = open_workbook_by_extension(path, options.password.as_deref())?;
+    let lang_config_sheet = options
+        .lang_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_LANG_CONFIG_SHEET);
+    let sheet_config_sheet = options
+        .sheet_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_SHEET_CONFIG_SHEET);
+    let mut sheets = Vec::new();
+    for name in workbook.sheet_names().to_vec() {
+        let range = match workbook.worksheet_range(&name) {
+            Some(Ok(range)) => range,
+            Some(Err(e)) => return Err(format!("读取工作表 {} 失败: {}", name, e).into()),
+            None => Range::empty(),
+        };
+        let mut headers: Vec<String> = range
+            .rows()
+            .next()
+            .unwrap_or_default()
+            .iter()
+            .map(|c| get_cell_string(c, ColumnType::Auto).trim().to_string())
+            .collect();
+        while headers.last().is_some_and(|h| h.is_empty()) {
+            headers.pop();
+        }
+        let role = if name == lang_config_sheet {
+            SheetRole::LanguageConfig
+        } else if name == sheet_config_sheet {
+            SheetRole::SheetConfig
+        } else if range.used_cells().next().is_none() {
+            SheetRole::Empty
+        } else {
+            SheetRole::Data
+        };
+        sheets.push(SheetInfo {
+            name,
+            headers,
+            row_count: range.height(),
+            role,
+        });
+    }
+    Ok(sheets)
+}
+
+/// 转换一个工作簿: 读取配置和各工作表，按选项写出各语言文件、校验报告并压缩。
+/// 不依赖 Tauri，进度和完成事件通过 `progress` 发出，可直接用于命令行工具或测试
+pub fn convert_workbook(
+    path: &Path,
+    options: ConvertOptions,
+    progress: &Progress,
+) -> Result<ConvertReport, ExportError> {
+    run_conversion(path, options, progress, None, None)
+}
+
+/// 转换一个工作簿，只返回各语言序列化后的 JSON (语言代码, 内容)，
+/// 不创建导出文件夹、不写文件也不压缩。校验仍然进行，有错误时返回 Err；
+/// output_format、validate_only、encoding 等只影响写文件的选项不起作用
+pub fn convert_workbook_to_strings(
+    path: &Path,
+    options: ConvertOptions,
+) -> Result<Vec<(String, String)>, ExportError> {
+    let mut outputs = Vec::new();
+    run_conversion(path, options, &Progress::silent(), Some(&mut outputs), None)?;
+    Ok(outputs)
+}
+
+/// `in_memory` 不为 None 时各语言的 JSON 放入其中，不写任何文件；
+/// `row_limit` 为每个工作表最多读取的数据行数，用于预览
+fn run_conversion(
+    path: &Path,
+    mut options: ConvertOptions,
+    progress: &Progress,
+    in_memory: Option<&mut Vec<(String, String)>>,
+    row_limit: Option<usize>,
+) -> Result<ConvertReport, ExportError> {
+    if in_memory.is_some() {
+        options.validate_only = false;
+    }
+    if row_limit.is_some() {
+        // 只读了部分行，必需 key 大多会显示为缺失，不再检查
+        options.required_keys = None;
+        options.required_keys_file = None;
+    }
+    let writes = !options.validate_only && in_memory.is_none();
+    let output_formats = parse_output_formats(&options.output_format)?;
+    let compression = parse_zip_compression(&options.compression, options.compression_level)?;
+    let archive = parse_archive_format(options.archive_format.as_deref(), options.skip_zip)?;
+    let nesting = parse_output_nesting(options.output_nesting.as_deref())?;
+    let plural = parse_plural_format(options.plural_format.as_deref())?;
+    let rtl_marks = parse_rtl_marks(options.rtl_marks.as_deref())?;
+    let key_naming = parse_key_naming(options.key_naming.as_deref())?;
+    let comment_style = parse_comment_style(options.comment_style.as_deref())?;
+    let combined_shape = parse_combined_shape(options.combined_shape.as_deref())?;
+    let root_wrapper = parse_root_wrapper(options.root_wrapper.as_deref())?;
+    let placeholder_syntax = PlaceholderSyntax::new(
+        &options.placeholder_delimiters,
+        parse_placeholder_style(options.placeholder_style.as_deref())?,
+    )?;
+    let status_column = options
+        .status_column
+        .as_deref()
+        .map(str::trim)
+        .filter(|c| !c.is_empty());
+    if status_column.is_some() && options.include_statuses.is_empty() {
+        return Err("status_column 需要同时指定 include_statuses"
+            .to_string()
+            .into());
+    }
+    let required_keys = load_required_keys(&options)?;
+    let base_language = options.base_language.as_deref().filter(|c| !c.is_empty());
+    if options.fallback_to_base && base_language.is_none() {
+        return Err("fallback_to_base 需要同时指定 base_language"
+            .to_string()
+            .into());
+    }
+    if options.emit_template && base_language.is_none() {
+        return Err("emit_template 需要同时指定 base_language"
+            .to_string()
+            .into());
+    }
+    let layout_pattern = parse_output_layout(options.layout.as_deref(), options.split_by_sheet)?;
+    let custom_pattern = options
+        .filename_pattern
+        .as_deref()
+        .filter(|p| !p.trim().is_empty());
+    if layout_pattern.is_some() && custom_pattern.is_some() {
+        return Err("layout 为 i18next 时不能同时指定 filename_pattern"
+            .to_string()
+            .into());
+    }
+    let filename_pattern = parse_filename_pattern(
+        custom_pattern.or(layout_pattern),
+        options.split_by_sheet,
+        &output_formats,
+    )?;
+    let encoding = parse_output_encoding(
+        options.encoding.as_deref(),
+        options.write_bom,
+        &output_formats,
+    )?;
+    let key_column = match options.key_column.as_deref() {
+        Some(s) if !s.trim().is_empty() => {
+            parse_column_index(s).ok_or_else(|| format!("key 列配置无效: '{}'", s.trim()))?
+        }
+        _ => 0,
+    };
+
+    send_progress(
+        progress,
+        &format!("开始处理文件: {}", path.display()),
+        LogType::Info,
+    )?;
+
+    let file_path = path.to_path_buf();
+    if !file_path.exists() {
+        let err = ExportError::FileNotFound {
+            path: file_path.display().to_string(),
+        };
+        send_progress(progress, &err.to_string(), LogType::Error)?;
+        return Err(err);
+    }
+
+    // 导出位置: 指定的输出目录或源文件所在目录
+    let parent = match &options.output_dir {
+        Some(dir) if !dir.trim().is_empty() => PathBuf::from(dir),
+        _ => file_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .to_path_buf(),
+    };
+    let custom_zip_path = options
+        .zip_path
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(PathBuf::from);
+    if writes {
+        let zip_dir = custom_zip_path
+            .as_ref()
+            .filter(|_| archive != ArchiveFormat::None)
+            .map(|p| match p.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => dir,
+                _ => Path::new("."),
+            });
+        let checked = ensure_writable_dir(&parent).and_then(|_| match zip_dir {
+            Some(dir) => ensure_writable_dir(dir),
+            None => Ok(()),
+        });
+        if let Err(msg) = checked {
+            send_progress(progress, &msg, LogType::Error)?;
+            return Err(msg.into());
+        }
+    }
+
+    send_progress(progress, "正在打开 Excel 文件...", LogType::Info)?;
+    let mut workbook = match open_workbook_by_extension(&file_path, options.password.as_deref()) {
+        Ok(w) => w,
+        Err(err) => {
+            send_progress(progress, &err.to_string(), LogType::Error)?;
+            return Err(err);
+        }
+    };
+    send_progress(progress, "Excel 文件已成功打开", LogType::Success)?;
+
+    let lang_config_sheet = options
+        .lang_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_LANG_CONFIG_SHEET);
+    let sheet_config_sheet = options
+        .sheet_config_sheet
+        .as_deref()
+        .unwrap_or(DEFAULT_SHEET_CONFIG_SHEET);
+    let config_file = options
+        .config_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty());
+    let mut config = match config_file {
+        Some(path) => load_config_file(Path::new(path))?,
+        None => Config::default(),
+    };
+    // 配置文件优先，工作簿中同时存在配置表时提示一下，避免误以为修改配置表会生效
+    let workbook_sheet_names = workbook.sheet_names().to_vec();
+    let note_config_file = |sheet: &str| -> Result<(), String> {
+        if workbook_sheet_names.iter().any(|n| n == sheet) {
+            send_progress(
+                progress,
+                &format!(
+                    "使用配置文件 {} 中的配置, 忽略工作表 {}",
+                    config_file.unwrap_or_default(),
+                    sheet
+                ),
+                LogType::Info,
+            )?;
+        }
+        Ok(())
+    };
+    let sheet_configs = match config.sheets.take() {
+        Some(entries) => {
+            note_config_file(sheet_config_sheet)?;
+            let mut configs = Vec::new();
+            for entry in entries {
+                configs.extend(parse_sheet_config(progress, entry)?);
+            }
+            configs
+        }
+        None => read_sheet_configs_from_excel(progress, &mut workbook, sheet_config_sheet)?,
+    };
+    // 显式的语言配置优先，没有语言配置表或开启 auto_languages 时从表头推断
+    let lang_sheet_missing = !workbook_sheet_names.iter().any(|n| n == lang_config_sheet);
+    let mut lang_configs = match config.languages.take() {
+        Some(entries) => {
+            note_config_file(lang_config_sheet)?;
+            entries
+                .into_iter()
+                .filter(|e| !e.code.trim().is_empty())
+                .map(parse_language_config)
+                .collect::<Result<Vec<_>, _>>()?
+        }
+        None if options.auto_languages || lang_sheet_missing => {
+            let reason = if options.auto_languages {
+                "已开启 auto_languages".to_string()
+            } else {
+                format!("没有语言配置表 {}", lang_config_sheet)
+            };
+            let configs =
+                infer_language_configs(&mut workbook, &sheet_configs, key_column, &options)?;
+            if configs.is_empty() {
+                return Err(format!("{}, 也无法从工作表表头推断出语言", reason).into());
+            }
+            let codes: Vec<&str> = configs.iter().map(|c| c.code.as_str()).collect();
+            send_progress(
+                progress,
+                &format!(
+                    "{}, 从工作表表头推断出 {} 个语言: {}",
+                    reason,
+                    codes.len(),
+                    codes.join(", ")
+                ),
+                LogType::Info,
+            )?;
+            configs
+        }
+        None => read_language_configs_from_excel(&mut workbook, lang_config_sheet)?,
+    };
+    // only_languages 之外的语言列也是已配置的，不算作无法识别的列
+    let language_codes: HashSet<String> = lang_configs.iter().map(|c| c.code.clone()).collect();
+    // 只作为回退来源的语言
+    let mut internal = vec![false; lang_configs.len()];
+    if let Some(only) = options.only_languages.as_ref().filter(|l| !l.is_empty()) {
+        for code in only {
+            if !lang_configs.iter().any(|c| &c.code == code) {
+                send_progress(
+                    progress,
+                    &format!("⚠️ 语言配置中没有指定的语言: {}", code),
+                    LogType::Warning,
+                )?;
+            }
+        }
+        // 回退链上的语言即使没有选中也要读取，作为回退来源但不写出
+        let mut needed: HashSet<String> = only.iter().cloned().collect();
+        loop {
+            let ancestors: Vec<String> = lang_configs
+                .iter()
+                .filter(|c| needed.contains(&c.code))
+                .filter_map(|c| match &c.fallback {
+                    Some(code) => Some(code.as_str()),
+                    None => base_language.filter(|_| options.fallback_to_base),
+                })
+                .filter(|code| !needed.contains(*code))
+                .map(str::to_string)
+                .collect();
+            if ancestors.is_empty() {
+                break;
+            }
+            needed.extend(ancestors);
+        }
+        lang_configs.retain(|c| needed.contains(&c.code));
+        internal = lang_configs
+            .iter()
+            .map(|c| !only.contains(&c.code))
+            .collect();
+        for config in lang_configs.iter().filter(|c| !only.contains(&c.code)) {
+            send_progress(
+                progress,
+                &format!("语言 {} 只作为回退来源读取, 不会写出", config.code),
+                LogType::Info,
+            )?;
+        }
+    }
+
+    send_progress(
+        progress,
+        &format!(
+            "读取到 {} 个语言, {} 个工作表",
+            lang_configs.len(),
+            sheet_configs.len()
+        ),
+        LogType::Info,
+    )?;
+
+    let fallback_base = match base_language.filter(|_| options.fallback_to_base) {
+        Some(code) => Some(
+            lang_configs
+                .iter()
+                .position(|c| c.code == code)
+                .ok_or_else(|| format!("回退使用的基准语言 {} 不在导出语言列表中", code))?,
+        ),
+        None => None,
+    };
+    let parents = resolve_fallback_parents(&lang_configs, fallback_base)?;
+    if let Some(code) = base_language.filter(|_| options.emit_template) {
+        if !lang_configs
+            .iter()
+            .zip(&internal)
+            .any(|(c, internal)| c.code == code && !internal)
+        {
+            return Err(format!("生成翻译模板使用的基准语言 {} 不在导出语言列表中", code).into());
+        }
+    }
+
+    // 创建导出目录
+    let stem = match options.folder_name.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            if name.contains(['/', '\\']) || name == "." || name == ".." {
+                return Err(format!("导出文件夹名称无效: '{}'", name).into());
+            }
+            name.into()
+        }
+        _ => file_path
+            .file_stem()
+            .map(|s| s.to_string_lossy())
+            .unwrap_or_else(|| "export".into()),
+    };
+    let export_time = Local::now();
+    let export_folder_name = if options.timestamped {
+        format!("{}_{}", stem, export_time.format("%Y%m%d_%H%M%S"))
+    } else {
+        stem.to_string()
+    };
+    let output_dir = parent.join(&export_folder_name);
+    let zip_path = custom_zip_path
+        .unwrap_or_else(|| parent.join(format!("{}.{}", export_folder_name, archive.extension())));
+    if zip_path.starts_with(&output_dir) {
+        return Err(format!("压缩包路径不能位于导出文件夹内: {}", zip_path.display()).into());
+    }
+    if options.validate_only {
+        send_progress(progress, "校验模式: 不会写入任何文件", LogType::Info)?;
+    } else if writes {
+        // 已有同名的导出结果时，开启 overwrite 才删除，否则新旧文件会混在一起
+        let existing: Vec<&Path> = [output_dir.as_path(), zip_path.as_path()]
+            .into_iter()
+            .filter(|p| p.exists())
+            .filter(|p| archive != ArchiveFormat::None || *p == output_dir)
+            // 增量更新时已有的 zip 保留下来，压缩时复用其中没有变化的条目
+            .filter(|p| !(options.incremental && archive == ArchiveFormat::Zip && *p == zip_path))
+            .collect();
+        for path in existing {
+            if !options.overwrite {
+                let msg = format!("{} 已存在, 开启 overwrite 才能覆盖", path.display());
+                send_progress(progress, &msg, LogType::Error)?;
+                return Err(msg.into());
+            }
+            let removed = if path.is_dir() {
+                fs::remove_dir_all(path)
+            } else {
+                fs::remove_file(path)
+            };
+            removed.map_err(|e| format!("删除已有的 {} 失败: {}", path.display(), e))?;
+            send_progress(
+                progress,
+                &format!("已删除已有的 {}", path.display()),
+                LogType::Info,
+            )?;
+        }
+        fs::create_dir_all(&output_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("导出文件夹创建完成: {}", output_dir.display()),
+            LogType::Success,
+        )?;
+    }
+    // 出错时只清理本次创建的导出目录，不写文件时可能是同名的已有目录
+    let created_dir = writes.then_some(output_dir.as_path());
+    let remove_created_dir = || {
+        if let Some(dir) = created_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    };
+
+    // 预先读取所有配置的工作表，之后各语言只读共享，无需再访问 workbook
+    let mut report = ExportReport::default();
+    let mut ranges = cache_sheet_ranges(progress, &mut workbook, &sheet_configs, &mut report)?;
+    if let Some(max_rows) = row_limit {
+        truncate_sheet_ranges(&mut ranges, &sheet_configs, max_rows);
+    }
+    if let Some(column) = status_column {
+        filter_rows_by_status(
+            progress,
+            &sheet_configs,
+            &mut ranges,
+            column,
+            &options.include_statuses,
+        )?;
+    }
+    check_key_text(
+        progress,
+        &sheet_configs,
+        &ranges,
+        key_column,
+        &options,
+        &mut report,
+    )?;
+    if let Some(naming) = key_naming {
+        let checked = check_key_naming(
+            progress,
+            &sheet_configs,
+            &ranges,
+            key_column,
+            &options,
+            naming,
+            &mut report,
+        );
+        if let Err(err) = checked {
+            remove_created_dir();
+            send_progress(progress, &err.to_string(), LogType::Error)?;
+            return Err(err);
+        }
+    }
+    if let Err(err) = check_key_collisions(
+        progress,
+        &sheet_configs,
+        &ranges,
+        key_column,
+        &options,
+        nesting,
+        &mut report,
+    ) {
+        remove_created_dir();
+        send_progress(progress, &err.to_string(), LogType::Error)?;
+        return Err(err);
+    }
+    check_unused_columns(
+        progress,
+        &sheet_configs,
+        &ranges,
+        &language_codes,
+        key_column,
+        &options,
+        &mut report,
+    )?;
+    let comments = match options.context_column.as_deref().map(str::trim) {
+        Some(column) if !column.is_empty() => Some(read_comments(
+            &sheet_configs,
+            &ranges,
+            key_column,
+            column,
+            &options,
+        )?),
+        _ => None,
+    };
+    // 批注只写入 notes.json，不写文件时不需要读取
+    let cell_notes = if options.extract_comments && writes {
+        Some(read_cell_notes(
+            progress,
+            &file_path,
+            &sheet_configs,
+            &ranges,
+            key_column,
+            &options,
+        )?)
+    } else {
+        None
+    };
+    let formulas = if options.formula_diagnostics {
+        cache_formula_ranges(&mut workbook, &sheet_configs)
+    } else {
+        HashMap::new()
+    };
+    let snapshot = match options.since_dir.as_deref().map(str::trim) {
+        Some(dir) if !dir.is_empty() => {
+            // 上次导出按同样的文件名模式命名
+            let mut snapshot = read_json_languages(progress, Path::new(dir), &filename_pattern)?;
+            // 上次导出同样包装过，比较时去掉外层路径
+            if !root_wrapper.is_empty() {
+                let prefix = format!("{}.", root_wrapper.join("."));
+                for flat in snapshot.values_mut() {
+                    *flat = flat
+                        .drain(..)
+                        .filter_map(|(k, v)| Some((k.strip_prefix(&prefix)?.to_string(), v)))
+                        .collect();
+                }
+            }
+            Some(snapshot)
+        }
+        _ => None,
+    };
+    let glossary = match options.glossary_sheet.as_deref().map(str::trim) {
+        Some(name) if !name.is_empty() => {
+            let range = read_config_sheet(&mut workbook, name)?;
+            // 术语表不需要出现在 sheet 配置中，出现时沿用其中的表头行和 key 列
+            let config = sheet_configs
+                .iter()
+                .find(|c| c.name == name)
+                .cloned()
+                .unwrap_or_else(|| SheetConfig::plain(name));
+            Some((range, config))
+        }
+        _ => None,
+    };
+
+    // 总工作量按 语言数 × 工作表数 计算
+    let ctx = ExportContext {
+        progress,
+        options: &options,
+        output_formats: &output_formats,
+        sheet_configs: &sheet_configs,
+        ranges: &ranges,
+        output_dir: &output_dir,
+        key_column,
+        nesting,
+        plural,
+        rtl_marks,
+        encoding,
+        glossary: glossary.as_ref().map(|(range, config)| (range, config)),
+        filename_pattern: &filename_pattern,
+        snapshot: snapshot.as_ref(),
+        formulas: &formulas,
+        inline_comments: comments
+            .as_ref()
+            .filter(|_| comment_style == CommentStyle::Inline),
+        comments: comments.as_ref(),
+        in_memory: !writes && !options.validate_only,
+        root_wrapper: &root_wrapper,
+        placeholders: &placeholder_syntax,
+        total_steps: (lang_configs.len() * sheet_configs.len()).max(1) as f32,
+        done_steps: AtomicUsize::new(0),
+    };
+
+    // 各语言的输出互不依赖，并行构建并写入，结果保持语言配置的顺序
+    // 任一语言失败时统一在这里清理导出目录
+    let finish = |output: LanguageOutput| {
+        if options.low_memory {
+            compact_language_output(&ctx, output)
+        } else {
+            output
+        }
+    };
+    let outputs = (|| {
+        // 回退需要上一级语言的完整数据: 每一轮处理回退语言都已完成的语言，同一轮内并行；
+        // 不是任何语言回退目标的结果可以立即压缩，其余的等所有语言完成后再压缩
+        let mut done: Vec<Option<LanguageOutput>> = (0..lang_configs.len()).map(|_| None).collect();
+        let is_parent = |i: usize| parents.contains(&Some(i));
+        while done.iter().any(|o| o.is_none()) {
+            let ready: Vec<usize> = (0..lang_configs.len())
+                .filter(|i| done[*i].is_none())
+                .filter(|i| parents[*i].is_none_or(|p| done[p].is_some()))
+                .collect();
+            let export = |i: usize| {
+                let parent = parents[i].and_then(|p| done[p].as_ref());
+                let output = export_language(&ctx, &lang_configs[i], parent, internal[i])?;
+                Ok::<_, ExportError>(if is_parent(i) { output } else { finish(output) })
+            };
+            let results = if options.low_memory {
+                ready
+                    .iter()
+                    .map(|i| export(*i))
+                    .collect::<Result<Vec<_>, _>>()?
+            } else {
+                ready
+                    .par_iter()
+                    .map(|i| export(*i))
+                    .collect::<Result<Vec<_>, _>>()?
+            };
+            for (i, output) in ready.into_iter().zip(results) {
+                done[i] = Some(output);
+            }
+        }
+        let outputs: Vec<LanguageOutput> = done
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !internal[*i])
+            .map(|(i, o)| {
+                let output = o.expect("所有语言都已处理");
+                if is_parent(i) {
+                    finish(output)
+                } else {
+                    output
+                }
+            })
+            .collect();
+        Ok::<_, ExportError>(outputs)
+    })();
+    let outputs = match outputs {
+        Ok(outputs) => outputs,
+        Err(err) => {
+            abort_if_cancelled(progress, created_dir)?;
+            remove_created_dir();
+            return Err(err);
+        }
+    };
+
+    let mut all_files = vec![];
+    let mut lang_data: Vec<(String, SheetDataMap)> = Vec::new();
+    let mut final_jsons: Vec<(String, JsonValue)> = Vec::new();
+    let mut fallback_counts: HashMap<String, usize> = HashMap::new();
+    for output in outputs {
+        fallback_counts.insert(output.code.clone(), output.fallback_count);
+        report.issues.extend(output.report.issues);
+        all_files.extend(output.files);
+        final_jsons.push((output.code.clone(), output.final_json));
+        lang_data.push((output.code, output.sheet_data_map));
+    }
+
+    // 各语言都读完后才检查，一次列出所有空值
+    if options.fail_on_empty && !options.validate_only {
+        let empties: Vec<Issue> = report
+            .issues
+            .iter()
+            .filter(|i| i.category == IssueCategory::EmptyValue)
+            .cloned()
+            .collect();
+        if !empties.is_empty() {
+            remove_created_dir();
+            let err = ExportError::EmptyValues { issues: empties };
+            send_progress(progress, &err.to_string(), LogType::Error)?;
+            return Err(err);
+        }
+    }
+
+    // 必需 key 与工作表内容无关，逐个语言检查
+    if !required_keys.is_empty() {
+        let mut missing_issues = Vec::new();
+        for (code, data) in &lang_data {
+            let missing = find_missing_required_keys(&required_keys, &sheet_configs, data);
+            if missing.is_empty() {
+                continue;
+            }
+            send_progress(
+                progress,
+                &format!(
+                    "语言 {} 缺少 {} 个必需 key: {}",
+                    code,
+                    missing.len(),
+                    missing.join(", ")
+                ),
+                LogType::Error,
+            )?;
+            missing_issues.extend(missing.iter().map(|key| Issue {
+                level: IssueLevel::Error,
+                category: IssueCategory::MissingRequiredKey,
+                language: Some(code.clone()),
+                key: Some(key.to_string()),
+                message: format!("缺少必需 key 语言: '{}' Key: '{}'", code, key),
+                ..Default::default()
+            }));
+        }
+        if !missing_issues.is_empty() && !options.validate_only {
+            remove_created_dir();
+            let err = ExportError::MissingRequiredKeys {
+                issues: missing_issues,
+            };
+            send_progress(progress, &err.to_string(), LogType::Error)?;
+            return Err(err);
+        }
+        report.issues.extend(missing_issues);
+    }
+
+    // 跨语言合并的格式在所有语言处理完后写入
+    if writes && output_formats.contains(&OutputFormat::CsvCombined) {
+        let output_path = output_dir.join("all.csv");
+        let bytes = encoding
+            .encode(&to_combined_csv(&final_jsons)?)
+            .map_err(|e| format!("{}: {}", output_path.display(), e))?;
+        fs::write(&output_path, bytes).map_err(|e| e.to_string())?;
+        send_progress(
+            progress,
+            &format!("✅ 已导出合并文件: {}", output_path.display()),
+            LogType::Success,
+        )?;
+        all_files.push(output_path);
+    }
+    if writes && options.combined {
+        let output_path = output_dir.join("all.json");
+        let combined = combine_languages(&final_jsons, combined_shape);
+        let content = serialize_output(
+            &combined,
+            OutputFormat::Json,
+            "",
+            &KeyNotes::default(),
+            &options,
+            &placeholder_syntax,
+        )?;
+        let bytes = encoding
+            .encode(&content)
+            .map_err(|e| format!("{}: {}", output_path.display(), e))?;
+        fs::write(&output_path, bytes).map_err(|e| e.to_string())?;
+        send_progress(
+            progress,
+            &format!("✅ 已导出合并文件: {}", output_path.display()),
+            LogType::Success,
+        )?;
+        all_files.push(output_path);
+    }
+
+    // 跨语言校验占位符名称是否一致
+    for issue in find_placeholder_mismatches(&lang_data, &placeholder_syntax) {
+        report.record(progress, issue)?;
+    }
+
+    // 与基准语言比较，报告缺失的 key
+    if let Some(base_code) = base_language {
+        match lang_data.iter().find(|(code, _)| code == base_code) {
+            Some((_, base)) => {
+                for (code, data) in lang_data.iter().filter(|(code, _)| code != base_code) {
+                    let mut missing = find_missing_keys(base, data);
+                    // 目标语言不使用的复数类别不算缺失
+                    if plural.is_some() {
+                        let required = required_plural_categories(code);
+                        missing.retain(|(_, key)| {
+                            split_plural_key(key).is_none_or(|(_, c)| required.contains(&c))
+                        });
+                    }
+                    for (sheet, key) in &missing {
+                        report.record(
+                            progress,
+                            Issue {
+                                category: IssueCategory::MissingKey,
+                                sheet: Some(sheet.to_string()),
+                                language: Some(code.clone()),
+                                key: Some(key.to_string()),
+                                message: format!(
+                                    "缺失翻译 语言: '{}' Sheet: '{}' Key: '{}'",
+                                    code, sheet, key
+                                ),
+                                ..Default::default()
+                            },
+                        )?;
+                    }
+                    send_progress(
+                        progress,
+                        &format!(
+                            "语言 {} 相对基准语言 {} 缺失 {} 个 key",
+                            code,
+                            base_code,
+                            missing.len()
+                        ),
+                        LogType::Info,
+                    )?;
+                }
+            }
+            None => {
+                report.record(
+                    progress,
+                    Issue {
+                        category: IssueCategory::MissingKey,
+                        language: Some(base_code.to_string()),
+                        message: format!("⚠️ 基准语言 {} 不在导出语言列表中", base_code),
+                        ..Default::default()
+                    },
+                )?;
+            }
+        }
+    }
+
+    let coverage = language_coverage(&lang_data, base_language, &fallback_counts);
+    for c in &coverage {
+        send_progress(
+            progress,
+            &format!(
+                "语言 {}: {} 个 key, {} 个有值, 完成度 {:.2}%",
+                c.lang, c.total_keys, c.non_empty_keys, c.coverage_pct
+            ),
+            LogType::Info,
+        )?;
+    }
+
+    // 校验模式到此结束，有错误时返回 Err 便于脚本判断
+    let error_count = report.count(IssueLevel::Error);
+    let warning_count = report.count(IssueLevel::Warning);
+    let mut complete = ConvertReport {
+        language_count: lang_data.len(),
+        warning_count,
+        error_count,
+        coverage,
+        issues: report.issues.clone(),
+        ..Default::default()
+    };
+    // 只返回内容时到此结束，不写报告和元数据，也不压缩
+    if let Some(strings) = in_memory {
+        for (code, value) in &final_jsons {
+            let content = serialize_output(
+                value,
+                OutputFormat::Json,
+                code,
+                &KeyNotes::default(),
+                &options,
+                &placeholder_syntax,
+            )?;
+            strings.push((code.clone(), content));
+        }
+        complete.message = format!("已生成 {} 个语言的 JSON 内容", strings.len());
+        send_complete(progress, &complete)?;
+        send_progress_pct(progress, &complete.message, LogType::Success, 1.0)?;
+        return Ok(complete);
+    }
+    if options.validate_only {
+        let summary = format!(
+            "校验完成: {} 个语言, {} 个错误, {} 个警告",
+            lang_data.len(),
+            error_count,
+            warning_count
+        );
+        complete.message = summary.clone();
+        send_complete(progress, &complete)?;
+        if error_count > 0 {
+            send_progress(progress, &summary, LogType::Error)?;
+            return Err(summary.into());
+        }
+        send_progress_pct(progress, &summary, LogType::Success, 1.0)?;
+        return Ok(complete);
+    }
+
+    // 写入校验报告，没有问题时也会生成空列表
+    let report_path = output_dir.join("report.json");
+    fs::write(&report_path, report.to_json()?).map_err(|e| format!("写入报告失败: {}", e))?;
+    send_progress(
+        progress,
+        &format!(
+            "已生成校验报告: {} ({} 个错误, {} 个警告)",
+            report_path.display(),
+            error_count,
+            warning_count
+        ),
+        LogType::Info,
+    )?;
+    let mut meta_path = None;
+    if options.emit_meta {
+        let path = output_dir.join("meta.json");
+        let meta = json!({
+            "source": file_path.file_name().map(|n| n.to_string_lossy()),
+            "exported_at": export_time.to_rfc3339_opts(SecondsFormat::Secs, false),
+            "languages": lang_data.iter().map(|(code, _)| code).collect::<Vec<_>>(),
+            "sheets": sheet_configs
+                .iter()
+                .map(|c| json!({ "name": c.name, "type": c.sheet_type }))
+                .collect::<Vec<_>>(),
+        });
+        let content =
+            serde_json::to_string_pretty(&meta).map_err(|e| format!("生成 meta 失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("写入 meta 失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("已生成元数据: {}", path.display()),
+            LogType::Info,
+        )?;
+        meta_path = Some(path);
+    }
+    let mut comments_path = None;
+    if let Some(comments) = comments
+        .as_ref()
+        .filter(|_| comment_style == CommentStyle::File)
+    {
+        let path = output_dir.join("comments.json");
+        let mut flat: IndexMap<String, String> = IndexMap::new();
+        for sheet_config in &sheet_configs {
+            let Some(sheet_comments) = comments.get(&sheet_config.name) else {
+                continue;
+            };
+            let prefix = sheet_config.key_prefix();
+            for (key, comment) in sheet_comments {
+                flat.insert(
+                    format!("{}{}", prefix, key.replace("\\.", ".")),
+                    comment.clone(),
+                );
+            }
+        }
+        let content =
+            serde_json::to_string_pretty(&flat).map_err(|e| format!("生成译者说明失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("写入译者说明失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("已生成译者说明: {}", path.display()),
+            LogType::Info,
+        )?;
+        comments_path = Some(path);
+    }
+    let mut notes_path = None;
+    if let Some(cell_notes) = &cell_notes {
+        let path = output_dir.join("notes.json");
+        let mut flat: IndexMap<String, &IndexMap<String, String>> = IndexMap::new();
+        for sheet_config in &sheet_configs {
+            let Some(sheet_notes) = cell_notes.get(&sheet_config.name) else {
+                continue;
+            };
+            let prefix = sheet_config.key_prefix();
+            for (key, notes) in sheet_notes {
+                flat.insert(format!("{}{}", prefix, key.replace("\\.", ".")), notes);
+            }
+        }
+        let content = serde_json::to_string_pretty(&flat)
+            .map_err(|e| format!("生成单元格批注失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("写入单元格批注失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("已生成单元格批注: {}", path.display()),
+            LogType::Info,
+        )?;
+        notes_path = Some(path);
+    }
+    let mut schema_path = None;
+    if options.emit_schema {
+        let path = output_dir.join("schema.json");
+        let schema = build_json_schema(
+            &final_jsons,
+            options.schema_patterns.then_some(&placeholder_syntax),
+        );
+        let content = serde_json::to_string_pretty(&schema)
+            .map_err(|e| format!("生成 schema 失败: {}", e))?;
+        fs::write(&path, content).map_err(|e| format!("写入 schema 失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("已生成 JSON Schema: {}", path.display()),
+            LogType::Info,
+        )?;
+        schema_path = Some(path);
+    }
+    let mut template_paths = Vec::new();
+    let template_source = base_language
+        .filter(|_| options.emit_template)
+        .and_then(|base| final_jsons.iter().find(|(code, _)| code == base));
+    if let Some((_, base_json)) = template_source {
+        let template = blank_json_values(base_json);
+        let mut contents = vec![(
+            "template.json",
+            serialize_output(
+                &template,
+                OutputFormat::Json,
+                "",
+                &KeyNotes::default(),
+                &options,
+                &placeholder_syntax,
+            )?,
+        )];
+        if output_formats.contains(&OutputFormat::Po) {
+            contents.push(("template.pot", to_po(&template, "", &HashMap::new())));
+        }
+        for (name, content) in contents {
+            template_paths.push(write_output_text(&ctx, name.into(), &content)?);
+        }
+    }
+    complete.file_paths = all_files
+        .iter()
+        .chain(std::iter::once(&report_path))
+        .chain(meta_path.as_ref())
+        .chain(schema_path.as_ref())
+        .chain(comments_path.as_ref())
+        .chain(notes_path.as_ref())
+        .chain(template_paths.iter())
+        .filter_map(|p| p.strip_prefix(&output_dir).ok())
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .collect();
+    // 所有文件都已写完，压缩前计算校验和；校验和文件本身不在列表中
+    if options.emit_checksums {
+        let path = output_dir.join(CHECKSUM_FILE);
+        fs::write(&path, sha256_manifest(&output_dir, &complete.file_paths)?)
+            .map_err(|e| format!("写入校验和失败: {}", e))?;
+        send_progress(
+            progress,
+            &format!("已生成校验和: {}", path.display()),
+            LogType::Info,
+        )?;
+        complete.file_paths.push(CHECKSUM_FILE.to_string());
+    }
+
+    abort_if_cancelled(progress, Some(&output_dir))?;
+    if archive == ArchiveFormat::None {
+        send_progress_pct(
+            progress,
+            &format!("✅ 已保留导出文件夹: {}", output_dir.display()),
+            LogType::Success,
+            1.0,
+        )?;
+        complete.output_dir = Some(output_dir.to_string_lossy().to_string());
+        complete.message = format!(
+            "完成导出 {} 个语言文件, 保存在文件夹 {:?}",
+            all_files.len(),
+            output_dir
+        );
+        send_complete(progress, &complete)?;
+        return Ok(complete);
+    }
+
+    // 压缩导出文件夹
+    send_progress(progress, "正在压缩导出文件夹...", LogType::Info)?;
+    match archive {
+        ArchiveFormat::TarGz => tar_gz_directory(Some(progress), &output_dir, &zip_path)?,
+        _ => zip_directory(
+            Some(progress),
+            &output_dir,
+            &zip_path,
+            compression,
+            options.incremental,
+        )?,
+    }
+    send_progress_pct(
+        progress,
+        &format!("✅ 已压缩文件夹为: {}", zip_path.display()),
+        LogType::Success,
+        1.0,
+    )?;
+
+    // 删除原始文件夹
+    if let Err(e) = fs::remove_dir_all(&output_dir) {
+        send_progress(
+            progress,
+            &format!("⚠️ 删除文件夹失败: {}", e),
+            LogType::Warning,
+        )?;
+        complete.output_dir = Some(output_dir.to_string_lossy().to_string());
+    }
+
+    complete.zip_path = Some(zip_path.to_string_lossy().to_string());
+    complete.message = format!(
+        "完成导出 {} 个语言文件并已压缩为 {:?}",
+        all_files.len(),
+        zip_path
+    );
+    send_complete(progress, &complete)?;
+    Ok(complete)
+}
+
+/// 批量转换中单个工作簿的结果
+#[derive(Serialize, Debug)]
+pub struct BatchFileResult {
+    pub path: String,
+    pub report: Option<ConvertReport>,
+    pub error: Option<ExportError>,
+}
+
+/// 批量转换的汇总结果
+#[derive(Serialize, Default, Debug)]
+pub struct BatchReport {
+    pub files: Vec<BatchFileResult>,
+    pub language_count: usize,
+    pub file_count: usize,
+    pub warning_count: usize,
+    pub error_count: usize,
+    pub failed_count: usize,
+    #[serde(skip)]
+    pub message: String,
+}
+
+/// 依次用同样的选项转换多个工作簿。
+/// 各文件的日志加上 `[文件名]` 前缀，进度折算为整批的进度；
+/// 单个文件失败只记录在结果中，取消时中止整批
+pub fn convert_batch(
+    paths: &[PathBuf],
+    options: &ConvertOptions,
+    progress: &Progress,
+) -> Result<BatchReport, ExportError> {
+    let mut batch = BatchReport::default();
+    let total = paths.len().max(1) as f32;
+    for (index, path) in paths.iter().enumerate() {
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.display().to_string());
+        let file_progress = Progress {
+            callback: Box::new(|event| match event {
+                ProgressEvent::Log {
+                    message,
+                    log_type,
+                    progress: pct,
+                } => progress.emit(ProgressEvent::Log {
+                    message: &format!("[{}] {}", name, message),
+                    log_type,
+                    progress: pct.map(|p| (index as f32 + p) / total),
+                }),
+                complete => progress.emit(complete),
+            }),
+            cancelled: progress.cancelled,
+        };
+
+        match convert_workbook(path, options.clone(), &file_progress) {
+            Ok(report) => {
+                batch.language_count += report.language_count;
+                batch.file_count += report.file_paths.len();
+                batch.warning_count += report.warning_count;
+                batch.error_count += report.error_count;
+                batch.files.push(BatchFileResult {
+                    path: path.display().to_string(),
+                    report: Some(report),
+                    error: None,
+                });
+            }
+            Err(ExportError::Cancelled) => return Err(ExportError::Cancelled),
+            Err(err) => {
+                send_progress(
+                    progress,
+                    &format!("[{}] 转换失败: {}", name, err),
+                    LogType::Error,
+                )?;
+                batch.failed_count += 1;
+                batch.files.push(BatchFileResult {
+                    path: path.display().to_string(),
+                    report: None,
+                    error: Some(err),
+                });
+            }
+        }
+    }
+
+    batch.message = format!(
+        "批量转换完成: {} 个工作簿 ({} 个失败), 共 {} 个语言, {} 个文件, {} 个警告",
+        paths.len(),
+        batch.failed_count,
+        batch.language_count,
+        batch.file_count,
+        batch.warning_count
+    );
+    let log_type = if batch.failed_count > 0 {
+        LogType::Warning
+    } else {
+        LogType::Success
+    };
+    send_progress_pct(progress, &batch.message, log_type, 1.0)?;
+    Ok(batch)
+}
+
+#[cfg_attr(mobile, tauri::mobile_entry_point)]
+pub fn run() {
+    tauri::Builder::default()
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_dialog::init())
-        .invoke_handler(tauri::generate_handler![convert_excel_to_json])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .manage(ExportState::default())
+        .manage(WatchState::default())
+        .invoke_handler(tauri::generate_handler![
+            convert_excel_to_json,
+            convert_excel_batch,
+            preview_excel_json,
+            preview_conversion,
+            validate_config,
+            inspect_workbook,
+            convert_json_to_excel,
+            diff_exports,
+            cancel_export,
+            watch_excel,
+            stop_watch
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // 退出时释放文件监听
+            if let tauri::RunEvent::Exit = event {
+                app.state::<WatchState>().watcher.lock().unwrap().take();
+            }
+        });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax(style: PlaceholderStyle) -> PlaceholderSyntax {
+        PlaceholderSyntax::new(&("{{".to_string(), "}}".to_string()), style).unwrap()
+    }
+
+    #[test]
+    fn printf_tokens_skip_escapes_and_words() {
+        assert_eq!(find_printf_token("Hi %s"), Some((3, "%s")));
+        assert_eq!(find_printf_token("%1$d items"), Some((0, "%1$d")));
+        assert_eq!(find_printf_token("%.2f%%"), Some((0, "%.2f")));
+        assert_eq!(find_printf_token("50% off"), None);
+        assert_eq!(find_printf_token("100%success"), None);
+        assert_eq!(find_printf_token("%%d"), None);
+    }
+
+    #[test]
+    fn default_style_does_not_flag_printf_tokens() {
+        let double_brace = syntax(PlaceholderStyle::DoubleBrace);
+        assert_eq!(
+            check_placeholders("Hi %s {{name}}", false, &double_brace),
+            Ok(())
+        );
+        assert!(check_placeholders("Hi %s", false, &syntax(PlaceholderStyle::Icu)).is_err());
+        assert!(
+            check_placeholders("Hi %s", false, &syntax(PlaceholderStyle::SingleBrace)).is_err()
+        );
+    }
+
+    #[test]
+    fn other_styles_reject_mixed_placeholders() {
+        let printf = syntax(PlaceholderStyle::Printf);
+        assert_eq!(check_placeholders("%1$s and %2$d", false, &printf), Ok(()));
+        assert_eq!(
+            check_placeholders("Hi {name}", false, &printf),
+            Err(
+                "第 4 个字符处的 '{name}' 是 single_brace 风格的占位符, 当前风格为 printf"
+                    .to_string()
+            )
+        );
+        let single = syntax(PlaceholderStyle::SingleBrace);
+        assert_eq!(check_placeholders("Hi {name}", false, &single), Ok(()));
+        assert!(check_placeholders("Hi {{name}}", false, &single).is_err());
+        assert_eq!(
+            check_placeholders("{{@brand}} {name}", false, &single),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn icu_messages() {
+        let ok = [
+            "Hi {name}",
+            "{count, plural, one {# item} other {# items}}",
+            "{count, plural, offset:1 =0 {none} one {{n} and one} other {#}}",
+            "{g, select, male {He} female {She} other {They}}",
+            "{n, number, percent}",
+            "It''s '{literal}' ok",
+        ];
+        for value in ok {
+            assert_eq!(check_icu_message(value), Ok(()), "{value}");
+        }
+        let errors = [
+            (
+                "{count, plural, one {# item}}",
+                "第 1 个字符处的 plural 缺少 other 分支",
+            ),
+            (
+                "{count, plural, one {#} other {#}",
+                "第 1 个字符处的占位符没有闭合",
+            ),
+            (
+                "{count, plural, single {x} other {y}}",
+                "第 17 个字符处的 plural 分支 'single' 无效",
+            ),
+            (
+                "{count, plural, one # other {x}}",
+                "第 17 个字符处的 plural 分支 'one' 后缺少 '{'",
+            ),
+            ("{n, bogus}", "第 1 个字符处的占位符类型 'bogus' 无效"),
+            ("a } b", "第 3 个字符处有单独的括号 '}'"),
+            ("{}", "第 1 个字符处的占位符为空"),
+        ];
+        for (value, message) in errors {
+            assert_eq!(
+                check_icu_message(value),
+                Err(message.to_string()),
+                "{value}"
+            );
+        }
+    }
+
+    fn header(cells: &[&str]) -> Vec<DataType> {
+        cells
+            .iter()
+            .map(|c| DataType::String(c.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn metadata_columns_are_never_language_columns() {
+        let row = header(&["key", "en", "#notes", "zh", "#zh"]);
+        assert_eq!(find_language_column(&row, "en"), Some(1));
+        assert_eq!(find_language_column(&row, "zh"), Some(3));
+        assert_eq!(find_language_column(&row, "#notes"), None);
+        assert_eq!(find_language_column(&row, "#zh"), None);
+        assert!(is_metadata_header("  #context"));
+        assert!(!is_metadata_header("en#"));
+    }
+
+    #[test]
+    fn dotted_keys_split_on_unescaped_dots() {
+        assert_eq!(split_dotted_key("menu.file.open"), ["menu", "file", "open"]);
+        assert_eq!(
+            split_dotted_key(r"version\.1.label"),
+            ["version.1", "label"]
+        );
+        assert_eq!(split_dotted_key("single"), ["single"]);
+    }
+
+    #[test]
+    fn output_nesting_values() {
+        assert_eq!(parse_output_nesting(None), Ok(OutputNesting::BySheet));
+        assert_eq!(
+            parse_output_nesting(Some(" Flat ")),
+            Ok(OutputNesting::Flat)
+        );
+        assert_eq!(
+            parse_output_nesting(Some("nested")),
+            Ok(OutputNesting::Nested)
+        );
+        assert!(parse_output_nesting(Some("tree")).is_err());
+    }
+
+    #[test]
+    fn nested_keys_expand_and_flatten_back() {
+        let mut obj = JsonValue::new_object();
+        insert_nested_key(&mut obj, "file.open", "Open".into()).unwrap();
+        insert_nested_key(&mut obj, "file.close", "Close".into()).unwrap();
+        insert_nested_key(&mut obj, r"v\.2", "Two".into()).unwrap();
+        assert_eq!(
+            obj.dump(),
+            r#"{"file":{"open":"Open","close":"Close"},"v.2":"Two"}"#
+        );
+
+        let mut flat = IndexMap::new();
+        flatten_json(&obj, "", &mut flat);
+        let keys: Vec<&str> = flat.keys().map(String::as_str).collect();
+        assert_eq!(keys, ["file.open", "file.close", r"v\.2"]);
+    }
+
+    #[test]
+    fn nested_key_conflicts_are_errors() {
+        let mut obj = JsonValue::new_object();
+        insert_nested_key(&mut obj, "file", "File".into()).unwrap();
+        assert!(insert_nested_key(&mut obj, "file.open", "Open".into()).is_err());
+        insert_nested_key(&mut obj, "edit.copy", "Copy".into()).unwrap();
+        assert!(insert_nested_key(&mut obj, "edit", "Edit".into()).is_err());
+        assert!(insert_nested_key(&mut obj, "a..b", "x".into()).is_err());
+    }
+
+    #[test]
+    fn sort_keys_is_case_insensitive_recursive_and_stable() {
+        let value =
+            json::parse(r#"{"b":"1","B":"2","a":{"z":"3","Y":"4"},"list":[{"d":"5","c":"6"}]}"#)
+                .unwrap();
+        let sorted = sort_json_keys(&value);
+        assert_eq!(
+            sorted.dump(),
+            r#"{"a":{"Y":"4","z":"3"},"B":"2","b":"1","list":[{"c":"6","d":"5"}]}"#
+        );
+        assert_eq!(sort_json_keys(&sorted).dump(), sorted.dump());
+    }
+
+    #[test]
+    fn double_brace_placeholders_are_tokenized() {
+        let braces = syntax(PlaceholderStyle::DoubleBrace);
+        let check = |value: &str, allow| check_placeholders(value, allow, &braces);
+        assert_eq!(check("Hi {{a}}, {{ b }}", false), Ok(()));
+        assert_eq!(
+            check("{a}", false),
+            Err("第 1 个字符处有单独的括号 '{'".to_string())
+        );
+        assert_eq!(check("{a}", true), Ok(()));
+        assert_eq!(
+            check("x {{a}", false),
+            Err("第 3 个字符处的占位符没有闭合".to_string())
+        );
+        assert_eq!(
+            check("{{}}", false),
+            Err("第 1 个字符处的占位符为空".to_string())
+        );
+        assert_eq!(
+            check("{{{{a}}}}", false),
+            Err("第 1 个字符处的占位符嵌套了 '{'".to_string())
+        );
+    }
+
+    #[test]
+    fn properties_escape_separators_spaces_and_non_ascii() {
+        assert_eq!(escape_properties("a=b:c", true, true), "a\\=b\\:c");
+        assert_eq!(escape_properties("my key", true, true), "my\\ key");
+        assert_eq!(
+            escape_properties(" lead trail", false, true),
+            "\\ lead trail"
+        );
+        assert_eq!(escape_properties("#!\\", false, true), "\\#\\!\\\\");
+        assert_eq!(escape_properties("a\nb\tc", false, true), "a\\nb\\tc");
+        assert_eq!(escape_properties("你好", false, true), "\\u4F60\\u597D");
+        assert_eq!(escape_properties("😀", false, true), "\\uD83D\\uDE00");
+        assert_eq!(escape_properties("你好", false, false), "你好");
+    }
+
+    #[test]
+    fn properties_flatten_nested_keys() {
+        let value = json::object! {
+            "menu": { "file.open": "打开 = Open" },
+            "ok": "OK",
+        };
+        assert_eq!(
+            to_properties(&value, false),
+            "menu.file.open=\\u6253\\u5F00 \\= Open\nok=OK\n"
+        );
+        assert_eq!(
+            to_properties(&value, true),
+            "menu.file.open=打开 \\= Open\nok=OK\n"
+        );
+    }
+
+    fn delimited(open: &str, close: &str) -> PlaceholderSyntax {
+        PlaceholderSyntax::new(
+            &(open.to_string(), close.to_string()),
+            PlaceholderStyle::DoubleBrace,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn square_bracket_delimiters() {
+        let syntax = delimited("[", "]");
+        assert!(check_placeholders("Hi [name], {{not}} [count]", false, &syntax).is_ok());
+        assert_eq!(
+            extract_placeholder_names("Hi [name], {{not}} [count]", &syntax),
+            BTreeSet::from(["count".to_string(), "name".to_string()])
+        );
+        assert!(check_placeholders("Hi [name", false, &syntax)
+            .unwrap_err()
+            .contains("没有闭合"));
+        assert!(check_placeholders("Hi []", false, &syntax)
+            .unwrap_err()
+            .contains("为空"));
+        assert!(check_placeholders("Hi [[name]]", false, &syntax)
+            .unwrap_err()
+            .contains("嵌套"));
+        assert!(check_placeholders("a ] b", false, &syntax)
+            .unwrap_err()
+            .contains("单独的括号 ']'"));
+        assert!(check_placeholders("a ] b", true, &syntax).is_ok());
+        assert_eq!(syntax.wrap("name"), "[name]");
+    }
+
+    #[test]
+    fn python_style_delimiters() {
+        let syntax = delimited("%(", ")s");
+        let value = "%(count)s items in %(folder)s (100%)";
+        assert!(check_placeholders(value, false, &syntax).is_ok());
+        assert_eq!(
+            extract_placeholder_names(value, &syntax),
+            BTreeSet::from(["count".to_string(), "folder".to_string()])
+        );
+        assert!(check_placeholders("%(count) items", false, &syntax)
+            .unwrap_err()
+            .contains("没有闭合"));
+        assert!(check_placeholders("%()s", false, &syntax)
+            .unwrap_err()
+            .contains("为空"));
+        assert_eq!(syntax.wrap("n"), "%(n)s");
+    }
+
+    #[test]
+    fn invalid_delimiters_are_rejected() {
+        let config = |open: &str, close: &str, style| {
+            PlaceholderSyntax::new(&(open.to_string(), close.to_string()), style).err()
+        };
+        assert!(config(" ", "]", PlaceholderStyle::DoubleBrace).is_some());
+        assert!(config("[", "", PlaceholderStyle::DoubleBrace).is_some());
+        assert!(config("[", "]", PlaceholderStyle::Printf)
+            .unwrap()
+            .contains("只适用于 double_brace"));
+    }
+
+    #[test]
+    fn combine_by_key_merges_objects_and_writes_leaves_per_language() {
+        let en = json::object! { "ok": "OK", "menu": { "open": "Open" }, "only_en": "x" };
+        let zh = json::object! { "menu": { "open": "打开", "close": "关闭" }, "ok": "好" };
+        let combined = combine_by_key(&[("en", &en), ("zh", &zh)]);
+        assert_eq!(
+            combined.dump(),
+            json::object! {
+                "ok": { "en": "OK", "zh": "好" },
+                "menu": {
+                    "open": { "en": "Open", "zh": "打开" },
+                    "close": { "zh": "关闭" },
+                },
+                "only_en": { "en": "x" },
+            }
+            .dump()
+        );
+
+        // 同一位置上一个语言是对象、另一个是字符串时整体作为叶子
+        let flat = json::object! { "menu": "Menu" };
+        assert_eq!(
+            combine_by_key(&[("en", &en), ("de", &flat)])["menu"].dump(),
+            json::object! { "en": { "open": "Open" }, "de": "Menu" }.dump()
+        );
+    }
+
+    #[test]
+    fn sha256_manifest_lists_files_in_sha256sum_format() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("en")).unwrap();
+        fs::write(dir.path().join("en/base.json"), "abc").unwrap();
+        fs::write(dir.path().join("empty.json"), "").unwrap();
+        let manifest = sha256_manifest(
+            dir.path(),
+            &["en/base.json".to_string(), "empty.json".to_string()],
+        )
+        .unwrap();
+        assert_eq!(
+            manifest,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad  en/base.json\n\
+             e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855  empty.json\n"
+        );
+        assert!(sha256_manifest(dir.path(), &["missing.json".to_string()])
+            .unwrap_err()
+            .contains("missing.json"));
+    }
+
+    #[test]
+    fn blank_json_values_keep_structure_and_order() {
+        let value = json::object! {
+            "z": "Z",
+            "menu": { "open": "Open", "count": 3 },
+            "list": ["a", { "b": "B" }],
+        };
+        assert_eq!(
+            blank_json_values(&value).dump(),
+            r#"{"z":"","menu":{"open":"","count":""},"list":["",{"b":""}]}"#
+        );
+    }
+
+    #[test]
+    fn xml_elements_match_whole_names_with_any_prefix() {
+        let xml = r#"<sheets><sheet name="a" r:id="rId1"/><x:sheet name="b"/><sheetPr code="c"/></sheets>"#;
+        let names: Vec<_> = xml_elements_attrs(xml, "sheet")
+            .iter()
+            .map(|attrs| attrs["name"])
+            .collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(xml_elements_attrs(xml, "sheet")[0]["r:id"], "rId1");
+    }
+
+    #[test]
+    fn sheet_keys_follow_the_export_rules() {
+        let mut range = Range::new((0, 0), (4, 1));
+        let cells = [
+            ((0, 0), "key"),
+            ((0, 1), "en"),
+            ((1, 0), " ok\u{200B}"),
+            ((1, 1), "OK"),
+            ((2, 1), "merged"),
+            ((3, 0), " "),
+            ((4, 0), "last"),
+        ];
+        for (pos, value) in cells {
+            range.set_value(pos, DataType::String(value.to_string()));
+        }
+        let sheet_config = SheetConfig::plain("common");
+        let header_row = range.rows().next().unwrap();
+        let keys = |options: &ConvertOptions| -> Vec<(usize, String)> {
+            sheet_keys(&range, &sheet_config, header_row, 0, options)
+                .map(|(row_idx, _, key)| (row_idx, key))
+                .collect()
+        };
+        assert_eq!(
+            keys(&ConvertOptions::default()),
+            [
+                (1, " ok\u{200B}".to_string()),
+                (3, " ".to_string()),
+                (4, "last".to_string())
+            ]
+        );
+        let options = ConvertOptions {
+            clean_invisible: true,
+            trim_keys: true,
+            fill_merged_keys: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            keys(&options),
+            [
+                (1, "ok".to_string()),
+                (2, "ok".to_string()),
+                (4, "last".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn out_of_range_date_serial_falls_back_to_number() {
+        let id = DataType::Float(1e9);
+        assert_eq!(get_cell_string(&id, ColumnType::Date), "1000000000");
+        assert_eq!(out_of_range_date(&id, ColumnType::Date), Some(1e9));
+        let id = DataType::Int(1_000_000_000);
+        assert_eq!(get_cell_string(&id, ColumnType::Date), "1000000000");
+        let dt = DataType::DateTime(1e12);
+        assert_eq!(get_cell_string(&dt, ColumnType::Auto), "1000000000000");
+
+        let date = DataType::Float(45000.0);
+        assert_eq!(get_cell_string(&date, ColumnType::Date), "2023-03-15");
+        assert_eq!(out_of_range_date(&date, ColumnType::Date), None);
+        assert_eq!(out_of_range_date(&id, ColumnType::Number), None);
+    }
+
+    fn language(date_format: &str, number_format: &str) -> Result<LanguageConfig, ExportError> {
+        parse_language_config(LanguageConfigEntry {
+            code: "de".into(),
+            date_format: Some(date_format.into()),
+            number_format: Some(number_format.into()),
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn number_format_follows_the_sample() {
+        let german = NumberFormat::parse("1.234,5").unwrap();
+        assert_eq!(german.format(1234567.25), "1.234.567,25");
+        assert_eq!(german.format(-1234.5), "-1.234,5");
+        assert_eq!(german.format(-12.0), "-12");
+        let french = NumberFormat::parse("1 234,5").unwrap();
+        assert_eq!(french.format(1234.5), "1 234,5");
+        assert_eq!(french.format(999.0), "999");
+        assert_eq!(
+            NumberFormat::parse("1234,5").unwrap().format(-1234.5),
+            "-1234,5"
+        );
+
+        assert!(NumberFormat::parse("1,234,5").is_none());
+        assert!(NumberFormat::parse("12345").is_none());
+        assert!(NumberFormat::parse("1.2345").is_none());
+    }
+
+    #[test]
+    fn local_date_and_number_use_the_language_formats() {
+        let de = language("%d.%m.%Y", "1.234,5").unwrap();
+        let date = DataType::Float(45000.5);
+        assert_eq!(
+            get_localized_cell_string(&date, ColumnType::LocalDate, &de),
+            "15.03.2023"
+        );
+        assert_eq!(
+            get_localized_cell_string(&DataType::Float(-1234.5), ColumnType::LocalNumber, &de),
+            "-1.234,5"
+        );
+        assert_eq!(
+            get_localized_cell_string(&DataType::Float(1e9), ColumnType::LocalDate, &de),
+            "1000000000"
+        );
+        // 语言没有配置格式时与 date 相同
+        let en = parse_language_config(LanguageConfigEntry {
+            code: "en".into(),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            get_localized_cell_string(&date, ColumnType::LocalDate, &en),
+            "2023-03-15"
+        );
+    }
+
+    #[test]
+    fn date_format_needing_a_timezone_is_rejected() {
+        for format in ["%d/%m/%Y %z", "%Y-%m-%d %Z", "%H:%M %:z", "%Q"] {
+            let err = language(format, "1.234,5").unwrap_err();
+            assert!(err.to_string().contains("日期格式无效"), "{format}: {err}");
+        }
+        assert!(language("%d/%m/%Y", "1,234.5").is_ok());
+        assert!(language("%d/%m/%Y", "1_234_5")
+            .unwrap_err()
+            .to_string()
+            .contains("数字格式无效"));
+    }
+
+    fn glossary(terms: &[(&str, &str)]) -> IndexMap<String, String> {
+        terms
+            .iter()
+            .map(|(term, value)| (term.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn glossary_expands_nested_terms_and_keeps_placeholders() {
+        let terms = glossary(&[("brand", "Acme"), ("product", "{{@brand}} Cloud")]);
+        assert_eq!(
+            resolve_glossary(
+                "Welcome to {{@ product }}, {{name}}",
+                &terms,
+                &mut Vec::new()
+            ),
+            Ok("Welcome to Acme Cloud, {{name}}".to_string())
+        );
+        assert_eq!(
+            resolve_glossary("Hi {{name}}", &terms, &mut Vec::new()),
+            Ok("Hi {{name}}".to_string())
+        );
+    }
+
+    #[test]
+    fn glossary_reports_cycles_and_undefined_terms() {
+        let terms = glossary(&[("a", "{{@b}}"), ("b", "x {{@a}}"), ("self", "{{@self}}")]);
+        assert_eq!(
+            resolve_glossary("{{@a}}", &terms, &mut Vec::new()),
+            Err("术语循环引用: a -> b -> a".to_string())
+        );
+        assert_eq!(
+            resolve_glossary("{{@self}}", &terms, &mut Vec::new()),
+            Err("术语循环引用: self -> self".to_string())
+        );
+        assert_eq!(
+            resolve_glossary("{{@missing}}", &terms, &mut Vec::new()),
+            Err("未定义的术语: missing".to_string())
+        );
+    }
+
+    #[test]
+    fn glossary_uses_the_sheet_header_row_and_key_column() {
+        let mut range = Range::new((0, 0), (3, 2));
+        let cells = [
+            ((0, 0), "术语表"),
+            ((1, 0), "note"),
+            ((1, 1), "term"),
+            ((1, 2), "en"),
+            ((2, 0), "公司名"),
+            ((2, 1), "brand"),
+            ((2, 2), "Acme"),
+            ((3, 1), "empty"),
+        ];
+        for (pos, value) in cells {
+            range.set_value(pos, DataType::String(value.to_string()));
+        }
+        let config = SheetConfig {
+            key_column: Some(1),
+            header_row: 1,
+            ..SheetConfig::plain("glossary")
+        };
+        let options = ConvertOptions::default();
+        assert_eq!(
+            read_glossary(&range, &config, "en", 0, &options),
+            Ok(glossary(&[("brand", "Acme")]))
+        );
+        assert_eq!(
+            read_glossary(&range, &config, "fr", 0, &options),
+            Ok(IndexMap::new())
+        );
+        let config = SheetConfig {
+            header_row: 9,
+            ..config
+        };
+        assert!(read_glossary(&range, &config, "en", 0, &options).is_err());
+    }
+
+    fn fallback_chain(languages: &[(&str, Option<&str>)]) -> Result<Vec<Option<usize>>, String> {
+        let configs: Vec<LanguageConfig> = languages
+            .iter()
+            .map(|(code, fallback)| {
+                parse_language_config(LanguageConfigEntry {
+                    code: code.to_string(),
+                    fallback: fallback.map(str::to_string),
+                    ..Default::default()
+                })
+                .unwrap()
+            })
+            .collect();
+        resolve_fallback_parents(&configs, None)
+    }
+
+    #[test]
+    fn fallback_parents_follow_the_chain() {
+        assert_eq!(
+            fallback_chain(&[
+                ("en", None),
+                ("en-GB", Some("en")),
+                ("en-AU", Some("en-GB"))
+            ]),
+            Ok(vec![None, Some(0), Some(1)])
+        );
+        let configs: Vec<LanguageConfig> = ["en", "zh"]
+            .map(|code| {
+                parse_language_config(LanguageConfigEntry {
+                    code: code.into(),
+                    ..Default::default()
+                })
+                .unwrap()
+            })
+            .into();
+        assert_eq!(
+            resolve_fallback_parents(&configs, Some(0)),
+            Ok(vec![None, Some(0)])
+        );
+    }
+
+    #[test]
+    fn fallback_cycles_and_missing_parents_are_errors() {
+        assert_eq!(
+            fallback_chain(&[("a", Some("b")), ("b", Some("a"))]),
+            Err("语言回退形成循环: a -> b -> a".to_string())
+        );
+        // 循环不经过第一个语言时从循环中的语言开始报告
+        assert_eq!(
+            fallback_chain(&[
+                ("x", Some("a")),
+                ("a", Some("b")),
+                ("b", Some("c")),
+                ("c", Some("a"))
+            ]),
+            Err("语言回退形成循环: a -> b -> c -> a".to_string())
+        );
+        assert_eq!(
+            fallback_chain(&[("en", Some("en"))]),
+            Err("语言回退形成循环: en -> en".to_string())
+        );
+        assert_eq!(
+            fallback_chain(&[("en-GB", Some("en"))]),
+            Err("语言 en-GB 的回退语言 en 不在导出语言列表中".to_string())
+        );
+    }
+
+    #[test]
+    fn failed_incremental_zip_leaves_no_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let src = dir.path().join("out");
+        fs::create_dir(&src).unwrap();
+        fs::write(src.join("en.json"), "{}").unwrap();
+        let zip_path = dir.path().join("out.zip");
+        let compression = parse_zip_compression("deflated", None).unwrap();
+        zip_directory(None, &src, &zip_path, compression, false).unwrap();
+        let before = fs::read(&zip_path).unwrap();
+
+        fs::write(src.join("en.json"), r#"{"ok":"OK"}"#).unwrap();
+        let failing = Progress::new(|_| Err("已取消".to_string()));
+        let err = zip_directory(Some(&failing), &src, &zip_path, compression, true).unwrap_err();
+        assert_eq!(err, "已取消");
+        assert!(!dir.path().join("out.zip.tmp").exists());
+        assert_eq!(fs::read(&zip_path).unwrap(), before);
+    }
+
+    #[test]
+    fn transform_ops_parse() {
+        assert_eq!(
+            TransformOp::parse(" Uppercase "),
+            Some(TransformOp::Uppercase)
+        );
+        assert_eq!(TransformOp::parse("trim"), Some(TransformOp::Trim));
+        assert_eq!(
+            TransformOp::parse("replace:foo:"),
+            Some(TransformOp::Replace("foo".into(), String::new()))
+        );
+        assert_eq!(
+            TransformOp::parse("replace:a:b:c"),
+            Some(TransformOp::Replace("a".into(), "b:c".into()))
+        );
+        assert_eq!(TransformOp::parse("replace::x"), None);
+        assert_eq!(TransformOp::parse("replace:foo"), None);
+        assert_eq!(TransformOp::parse("reverse"), None);
+    }
+
+    fn transform_config(spec: &str) -> Result<SheetConfig, ExportError> {
+        let entry = SheetConfigEntry {
+            name: "common".into(),
+            transforms: Some(spec.into()),
+            ..Default::default()
+        };
+        parse_sheet_config(&Progress::silent(), entry).map(Option::unwrap)
+    }
+
+    #[test]
+    fn transform_spec_splits_languages_and_operations() {
+        let config = transform_config("trim; en=uppercase|replace:OK:Okay\nreplace:a=b:c").unwrap();
+        assert_eq!(
+            config.transforms,
+            [
+                (None, TransformOp::Trim),
+                (Some("en".into()), TransformOp::Uppercase),
+                (
+                    Some("en".into()),
+                    TransformOp::Replace("OK".into(), "Okay".into())
+                ),
+                // `=` 在第一个冒号之后，属于替换的参数而不是语言
+                (None, TransformOp::Replace("a=b".into(), "c".into())),
+            ]
+        );
+        assert_eq!(config.transforms_for("en").ops.len(), 4);
+        assert_eq!(config.transforms_for("zh").ops.len(), 2);
+
+        let err = transform_config("en=uppercase|reverse").unwrap_err();
+        assert!(err.to_string().contains("'reverse'"), "{err}");
+    }
+
+    #[test]
+    fn transforms_keep_placeholders_unchanged() {
+        let config = transform_config("replace:name:user; en=uppercase; trim").unwrap();
+        let syntax = syntax(PlaceholderStyle::DoubleBrace);
+        assert_eq!(
+            config
+                .transforms_for("en")
+                .apply(" Hi {{name}}, your name ", &syntax),
+            "HI {{name}}, YOUR USER"
+        );
+        assert_eq!(
+            config.transforms_for("zh").apply("name {{name}}", &syntax),
+            "user {{name}}"
+        );
+        let config = transform_config("lowercase").unwrap();
+        assert_eq!(
+            config.transforms_for("en").apply("OK {{Count}}", &syntax),
+            "ok {{Count}}"
+        );
+    }
 }
@@ -0,0 +1,244 @@
+//! 导出文件夹的归档: zip 的可复现性和增量更新、tar.gz
+
+mod common;
+
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::Mutex;
+
+use common::{export, fixture, options, write_workbook};
+use excel2json_lib::{convert_workbook, Progress, ProgressEvent};
+use serde_json::json;
+
+fn zip_archive(path: &Path) -> zip::ZipArchive<File> {
+    zip::ZipArchive::new(File::open(path).unwrap()).unwrap()
+}
+
+#[test]
+fn zip_is_sorted_with_fixed_timestamps_and_reproducible() {
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    let options = json!({ "skipZip": false, "splitBySheet": true });
+    let a = export(&fixture("basic.xlsx"), first.path(), options.clone()).unwrap();
+    let b = export(&fixture("basic.xlsx"), second.path(), options).unwrap();
+
+    let (a, b) = (a.zip_path.unwrap(), b.zip_path.unwrap());
+    assert_eq!(fs::read(&a).unwrap(), fs::read(&b).unwrap());
+
+    let mut archive = zip_archive(a.as_ref());
+    let names: Vec<String> = (0..archive.len())
+        .map(|i| archive.by_index(i).unwrap().name().to_string())
+        .collect();
+    let mut in_order = names.clone();
+    in_order.sort();
+    assert_eq!(names, in_order);
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i).unwrap();
+        let modified = entry.last_modified();
+        assert_eq!(
+            (modified.year(), modified.month(), modified.day()),
+            (1980, 1, 1),
+            "{}",
+            entry.name()
+        );
+    }
+}
+
+fn two_language_workbook(path: &std::path::Path, zh_ok: &str) {
+    write_workbook(
+        path,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            ("common", &[&["key", "en", "zh"], &["ok", "OK", zh_ok]]),
+        ],
+    );
+}
+
+/// 按原样重写 zip，条目使用 2000-01-01 的修改时间，`extra` 不为空时追加一个条目
+fn restamp_zip(path: &Path, extra: Option<&str>) {
+    let mut archive = zip_archive(path);
+    let mut entries = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i).unwrap();
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content).unwrap();
+        entries.push((entry.name().to_string(), entry.compression(), content));
+    }
+    if let Some(name) = extra {
+        entries.push((name.to_string(), entries[0].1, b"{}".to_vec()));
+    }
+    let comment = String::from_utf8(archive.comment().to_vec()).unwrap();
+    let mut zip = zip::ZipWriter::new(File::create(path).unwrap());
+    zip.set_comment(comment);
+    for (name, method, content) in entries {
+        let options = zip::write::FileOptions::default()
+            .compression_method(method)
+            .last_modified_time(zip::DateTime::from_date_and_time(2000, 1, 1, 0, 0, 0).unwrap());
+        zip.start_file(name, options).unwrap();
+        zip.write_all(&content).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+/// 各条目的 (名称, 修改年份, 内容)
+fn zip_entries(path: &Path) -> Vec<(String, u16, String)> {
+    let mut archive = zip_archive(path);
+    (0..archive.len())
+        .map(|i| {
+            let mut entry = archive.by_index(i).unwrap();
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            (
+                entry.name().to_string(),
+                entry.last_modified().year(),
+                content,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn incremental_zip_copies_unchanged_entries_and_rewrites_changed_ones() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    two_language_workbook(&book, "好");
+    let options = json!({ "skipZip": false, "incremental": true, "overwrite": true });
+    let zip_path = export(&book, dir.path(), options.clone())
+        .unwrap()
+        .zip_path
+        .unwrap();
+    restamp_zip(zip_path.as_ref(), None);
+
+    two_language_workbook(&book, "好的");
+    export(&book, dir.path(), options).unwrap();
+
+    let entries = zip_entries(zip_path.as_ref());
+    let entry = |name: &str| entries.iter().find(|(n, ..)| n == name).unwrap();
+    assert_eq!(entry("out/en.json").1, 2000);
+    assert_eq!(entry("out/zh.json").1, 1980);
+    assert!(entry("out/zh.json").2.contains("好的"));
+    assert!(!Path::new(&format!("{zip_path}.tmp")).exists());
+}
+
+#[test]
+fn incremental_zip_rebuilds_when_entries_were_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    two_language_workbook(&book, "好");
+    let options = json!({ "skipZip": false, "incremental": true, "overwrite": true });
+    let zip_path = export(&book, dir.path(), options.clone())
+        .unwrap()
+        .zip_path
+        .unwrap();
+    restamp_zip(zip_path.as_ref(), Some("out/stale.json"));
+
+    export(&book, dir.path(), options).unwrap();
+
+    let entries = zip_entries(zip_path.as_ref());
+    assert!(entries.iter().all(|(name, ..)| name != "out/stale.json"));
+    assert!(entries.iter().all(|(_, year, _)| *year == 1980));
+    assert_eq!(entries.len(), 3);
+}
+
+#[test]
+fn tar_gz_is_reproducible_and_keeps_long_paths() {
+    let folder = format!("export_{}", "x".repeat(110));
+    let options = json!({ "skipZip": false, "archiveFormat": "tar.gz", "folderName": folder });
+    let first = tempfile::tempdir().unwrap();
+    let second = tempfile::tempdir().unwrap();
+    let a = export(&fixture("basic.xlsx"), first.path(), options.clone()).unwrap();
+    let b = export(&fixture("basic.xlsx"), second.path(), options).unwrap();
+
+    let (a, b) = (a.zip_path.unwrap(), b.zip_path.unwrap());
+    assert!(a.ends_with(".tar.gz"), "{a}");
+    let bytes = fs::read(&a).unwrap();
+    assert_eq!(bytes, fs::read(&b).unwrap());
+
+    let mut archive = tar::Archive::new(flate2::read::GzDecoder::new(bytes.as_slice()));
+    let mut names = Vec::new();
+    for entry in archive.entries().unwrap() {
+        let mut entry = entry.unwrap();
+        let header = entry.header();
+        assert_eq!(
+            (
+                header.mode().unwrap(),
+                header.mtime().unwrap(),
+                header.uid().unwrap()
+            ),
+            (0o644, 0, 0)
+        );
+        let name = entry.path().unwrap().to_string_lossy().into_owned();
+        if name.ends_with("en.json") {
+            let mut content = String::new();
+            entry.read_to_string(&mut content).unwrap();
+            assert!(content.contains("\"ok\""), "{content}");
+        }
+        names.push(name);
+    }
+    assert_eq!(
+        names,
+        ["en.json", "report.json", "zh.json"].map(|f| format!("{folder}/{f}"))
+    );
+}
+
+#[test]
+fn zip_progress_is_reported_without_app_handle() {
+    let dir = tempfile::tempdir().unwrap();
+    let messages = Mutex::new(Vec::new());
+    let progress = Progress::new(|event| {
+        if let ProgressEvent::Log { message, .. } = event {
+            messages.lock().unwrap().push(message.to_string());
+        }
+        Ok(())
+    });
+    let report = convert_workbook(
+        &fixture("basic.xlsx"),
+        options(dir.path(), json!({ "skipZip": false })),
+        &progress,
+    )
+    .unwrap();
+    drop(progress);
+
+    let entries = zip_archive(report.zip_path.unwrap().as_ref()).len();
+    let messages = messages.into_inner().unwrap();
+    let zipping: Vec<&String> = messages
+        .iter()
+        .filter(|m| m.starts_with("正在压缩 "))
+        .collect();
+    assert_eq!(zipping.len(), entries);
+    assert!(zipping
+        .last()
+        .unwrap()
+        .starts_with(&format!("正在压缩 {0}/{0}: ", entries)));
+}
+
+#[test]
+fn incremental_zip_rebuilds_when_compression_level_changes() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    two_language_workbook(&book, "好");
+    let options = |level: i32| {
+        json!({
+            "skipZip": false,
+            "incremental": true,
+            "overwrite": true,
+            "compressionLevel": level,
+        })
+    };
+    let zip_path = export(&book, dir.path(), options(6))
+        .unwrap()
+        .zip_path
+        .unwrap();
+    restamp_zip(zip_path.as_ref(), None);
+
+    export(&book, dir.path(), options(9)).unwrap();
+
+    let entries = zip_entries(zip_path.as_ref());
+    assert!(entries.iter().all(|(_, year, _)| *year == 1980));
+    assert_eq!(
+        zip_archive(zip_path.as_ref()).comment(),
+        b"excel2json Deflated level 9"
+    );
+}
@@ -0,0 +1,68 @@
+//! 集成测试共用的工具: 生成小型工作簿、调用 convert_workbook 并读取导出结果
+#![allow(dead_code)]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use excel2json_lib::{convert_workbook, ConvertOptions, ConvertReport, ExportError, Progress};
+use serde_json::Value;
+
+/// tests/fixtures 下的工作簿
+pub fn fixture(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/fixtures")
+        .join(name)
+}
+
+/// 按 (工作表名, 各行) 写出工作簿。空字符串的单元格不写入，`=n:` 开头的写成数字
+pub fn write_workbook(path: &Path, sheets: &[(&str, &[&[&str]])]) {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    for (name, rows) in sheets {
+        let worksheet = workbook.add_worksheet();
+        worksheet.set_name(*name).unwrap();
+        for (r, row) in rows.iter().enumerate() {
+            for (c, value) in row.iter().enumerate() {
+                if value.is_empty() {
+                    continue;
+                }
+                match value.strip_prefix("=n:") {
+                    Some(n) => {
+                        worksheet.write_number(r as u32, c as u16, n.parse::<f64>().unwrap())
+                    }
+                    None => worksheet.write_string(r as u32, c as u16, *value),
+                }
+                .unwrap();
+            }
+        }
+    }
+    workbook.save(path).unwrap();
+}
+
+/// 导出选项: 以前端传入的 camelCase JSON 为准，默认导出到 `dir/out` 且不压缩
+pub fn options(dir: &Path, overrides: Value) -> ConvertOptions {
+    let mut value = serde_json::json!({
+        "outputDir": dir,
+        "folderName": "out",
+        "timestamped": false,
+        "skipZip": true,
+    });
+    value
+        .as_object_mut()
+        .unwrap()
+        .extend(overrides.as_object().unwrap().clone());
+    serde_json::from_value(value).unwrap()
+}
+
+/// 导出到 `dir/out`
+pub fn export(path: &Path, dir: &Path, overrides: Value) -> Result<ConvertReport, ExportError> {
+    convert_workbook(path, options(dir, overrides), &Progress::silent())
+}
+
+/// 导出文件夹中的文件
+pub fn output_file(report: &ConvertReport, name: &str) -> PathBuf {
+    Path::new(report.output_dir.as_deref().unwrap()).join(name)
+}
+
+pub fn read_json(path: &Path) -> Value {
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
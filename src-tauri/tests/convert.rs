@@ -0,0 +1,1131 @@
+//! 以 tests/fixtures 中的工作簿驱动 convert_workbook，检查导出的 JSON 和校验报告
+
+mod common;
+
+use std::fs::{self, File};
+use std::io::Read;
+
+use common::{export, fixture, options, output_file, read_json, write_workbook};
+use excel2json_lib::{
+    convert_json_dir_to_excel, convert_workbook_to_strings, ExportError, IssueCategory, IssueLevel,
+    Progress,
+};
+use serde_json::json;
+
+#[test]
+fn root_sheets_merge_into_top_level_and_nested_sheets_expand() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+
+    assert_eq!(
+        read_json(&output_file(&report, "en.json")),
+        json!({
+            "ok": "Okay",
+            "hello": "Hi {{name}}",
+            "menu": { "file": { "open": "Open", "close": "Close" } },
+            "errors": { "e1": "Bad" },
+            "partial": { "only": "Only" }
+        })
+    );
+}
+
+#[test]
+fn sheet_without_language_column_is_left_out() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+
+    let zh = read_json(&output_file(&report, "zh.json"));
+    assert_eq!(zh["errors"], json!({ "e1": "坏" }));
+    assert!(zh.get("partial").is_none());
+}
+
+#[test]
+fn empty_values_are_written_and_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+
+    let zh = read_json(&output_file(&report, "zh.json"));
+    assert_eq!(zh["menu"]["file"]["close"], "");
+    let empty: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::EmptyValue)
+        .collect();
+    assert_eq!(empty.len(), 1);
+    assert_eq!(empty[0].sheet.as_deref(), Some("menu"));
+    assert_eq!(empty[0].row, Some(3));
+    assert_eq!(empty[0].language.as_deref(), Some("zh"));
+    assert_eq!(empty[0].key.as_deref(), Some("file.close"));
+}
+
+#[test]
+fn duplicate_keys_keep_the_last_value_and_warn_per_language() {
+    let dir = tempfile::tempdir().unwrap();
+    let report = export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+
+    assert_eq!(read_json(&output_file(&report, "zh.json"))["ok"], "好的");
+    let duplicates: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::DuplicateKey)
+        .map(|i| (i.language.as_deref().unwrap(), i.row.unwrap()))
+        .collect();
+    assert_eq!(duplicates, [("en", 4), ("zh", 4)]);
+    assert_eq!(report.warning_count, 3);
+}
+
+#[test]
+fn placeholder_error_aborts_the_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = export(&fixture("bad_placeholder.xlsx"), dir.path(), json!({})).unwrap_err();
+
+    match err {
+        ExportError::PlaceholderError {
+            sheet,
+            row,
+            key,
+            value,
+            ..
+        } => {
+            assert_eq!((sheet.as_str(), row, key.as_str()), ("common", 3, "greet"));
+            assert_eq!(value, "Hi {{name}");
+        }
+        other => panic!("unexpected error: {other}"),
+    }
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn validate_only_records_placeholder_error_without_writing() {
+    let dir = tempfile::tempdir().unwrap();
+    let err = export(
+        &fixture("bad_placeholder.xlsx"),
+        dir.path(),
+        json!({ "validateOnly": true }),
+    )
+    .unwrap_err();
+
+    assert!(err.to_string().contains("1 个错误"), "{err}");
+    assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn notes_column_between_languages_is_not_exported() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("notes.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "#notes", "zh"],
+                    &["ok", "OK", "按钮文字", "好"],
+                ],
+            ),
+        ],
+    );
+    let report = export(&book, dir.path(), json!({})).unwrap();
+
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK" })
+    );
+    assert_eq!(
+        read_json(&dir.path().join("out/zh.json")),
+        json!({ "ok": "好" })
+    );
+    assert_eq!(report.warning_count, 0);
+}
+
+/// key 列纵向合并的工作表: 合并区域中只有首行有 key
+fn merged_key_workbook(path: &std::path::Path) {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let config = workbook.add_worksheet();
+    config.set_name("导出语言管理").unwrap();
+    config.write_string(0, 0, "en").unwrap();
+    let sheets = workbook.add_worksheet();
+    sheets.set_name("导出sheet管理").unwrap();
+    sheets.write_string(0, 0, "common").unwrap();
+    sheets.write_string(0, 1, "root").unwrap();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("common").unwrap();
+    sheet.write_row(0, 0, ["key", "en"]).unwrap();
+    sheet
+        .merge_range(1, 0, 2, 0, "title", &rust_xlsxwriter::Format::new())
+        .unwrap();
+    sheet.write_string(1, 1, "Title").unwrap();
+    sheet.write_string(2, 1, "Long title").unwrap();
+    sheet.write_row(3, 0, ["ok", "OK"]).unwrap();
+    workbook.save(path).unwrap();
+}
+
+#[test]
+fn merged_key_cells_fill_forward_when_enabled() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("merged.xlsx");
+    merged_key_workbook(&book);
+
+    let report = export(&book, dir.path(), json!({})).unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "title": "Title", "ok": "OK" })
+    );
+    assert_eq!(report.warning_count, 0);
+
+    let report = export(
+        &book,
+        dir.path(),
+        json!({ "fillMergedKeys": true, "overwrite": true }),
+    )
+    .unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "title": "Long title", "ok": "OK" })
+    );
+    let duplicate = report
+        .issues
+        .iter()
+        .find(|i| i.category == IssueCategory::DuplicateKey)
+        .unwrap();
+    assert_eq!(
+        (duplicate.row, duplicate.key.as_deref()),
+        (Some(3), Some("title"))
+    );
+}
+
+#[test]
+fn output_nesting_applies_to_every_sheet() {
+    let dir = tempfile::tempdir().unwrap();
+    export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({ "outputNesting": "flat", "onlyLanguages": ["en"] }),
+    )
+    .unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({
+            "ok": "Okay",
+            "hello": "Hi {{name}}",
+            "menu.file.open": "Open",
+            "menu.file.close": "Close",
+            "errors.e1": "Bad",
+            "partial.only": "Only"
+        })
+    );
+
+    export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({ "outputNesting": "nested", "onlyLanguages": ["en"], "overwrite": true }),
+    )
+    .unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json"))["menu"],
+        json!({ "file": { "open": "Open", "close": "Close" } })
+    );
+}
+
+#[test]
+fn sort_keys_orders_written_file() {
+    let dir = tempfile::tempdir().unwrap();
+    export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({ "sortKeys": true, "onlyLanguages": ["en"], "indent": 0 }),
+    )
+    .unwrap();
+    let text = std::fs::read_to_string(dir.path().join("out/en.json")).unwrap();
+    assert_eq!(
+        text,
+        r#"{"errors":{"e1":"Bad"},"hello":"Hi {{name}}","menu":{"file":{"close":"Close","open":"Open"}},"ok":"Okay","partial":{"only":"Only"}}"#
+    );
+}
+
+#[test]
+fn split_by_sheet_writes_one_file_per_sheet_and_zip_keeps_folders() {
+    let dir = tempfile::tempdir().unwrap();
+    let merged = export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+    assert_eq!(merged.file_paths, ["en.json", "zh.json", "report.json"]);
+
+    let split = tempfile::tempdir().unwrap();
+    let report = export(
+        &fixture("basic.xlsx"),
+        split.path(),
+        json!({ "splitBySheet": true, "rootFileName": "base", "skipZip": false }),
+    )
+    .unwrap();
+    assert_eq!(
+        report.file_paths,
+        [
+            "en/base.json",
+            "en/menu.json",
+            "en/errors.json",
+            "en/partial.json",
+            "zh/base.json",
+            "zh/menu.json",
+            "zh/errors.json",
+            "report.json"
+        ]
+    );
+
+    let zip_path = report.zip_path.unwrap();
+    let mut archive = zip::ZipArchive::new(File::open(zip_path).unwrap()).unwrap();
+    let mut menu = String::new();
+    archive
+        .by_name("out/en/menu.json")
+        .unwrap()
+        .read_to_string(&mut menu)
+        .unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&menu).unwrap(),
+        json!({ "file": { "open": "Open", "close": "Close" } })
+    );
+    assert!(archive.by_name("out/en.json").is_err());
+}
+
+#[test]
+fn indent_controls_json_bytes() {
+    let dir = tempfile::tempdir().unwrap();
+    let zh = dir.path().join("out/zh.json");
+    // 该工作簿只有 en 列的占位符有误，只导出 zh
+    let zh_only = |indent: u16, overwrite: bool| json!({ "indent": indent, "onlyLanguages": ["zh"], "overwrite": overwrite });
+
+    export(
+        &fixture("bad_placeholder.xlsx"),
+        dir.path(),
+        zh_only(0, false),
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&zh).unwrap(),
+        r#"{"ok":"好","greet":"你好 {{name}}"}"#
+    );
+
+    export(
+        &fixture("bad_placeholder.xlsx"),
+        dir.path(),
+        zh_only(4, true),
+    )
+    .unwrap();
+    assert_eq!(
+        std::fs::read_to_string(&zh).unwrap(),
+        "{\n    \"ok\": \"好\",\n    \"greet\": \"你好 {{name}}\"\n}"
+    );
+}
+
+/// 行长短不一的工作表: 只有 key 的行、中间的空行、右侧远处的单元格和末尾的空行
+fn ragged_workbook(path: &std::path::Path) {
+    write_workbook(
+        path,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "zh"],
+                    &["a", "A", "甲"],
+                    &["b"],
+                    &["c", "C"],
+                    &[],
+                    &["", "", "", "", "", "备注"],
+                    &["d", "D", "丁"],
+                    &[],
+                    &[],
+                    &["", "", "", "", "", ""],
+                ],
+            ),
+        ],
+    );
+}
+
+#[test]
+fn ragged_rows_are_read_as_empty_cells() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("ragged.xlsx");
+    ragged_workbook(&book);
+
+    let report = export(&book, dir.path(), json!({})).unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "a": "A", "b": "", "c": "C", "d": "D" })
+    );
+    assert_eq!(
+        read_json(&dir.path().join("out/zh.json")),
+        json!({ "a": "甲", "b": "", "c": "", "d": "丁" })
+    );
+    assert_eq!(report.error_count, 0);
+}
+
+#[test]
+fn key_column_beyond_sheet_width_does_not_panic() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("ragged.xlsx");
+    ragged_workbook(&book);
+
+    let err = export(&book, dir.path(), json!({ "keyColumn": "K" })).unwrap_err();
+    assert!(err.to_string().contains("超出范围"), "{err}");
+}
+
+#[test]
+fn low_memory_output_matches_normal_export_on_large_sheet() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("large.xlsx");
+    let rows: Vec<[String; 3]> = std::iter::once(["key".into(), "en".into(), "zh".into()])
+        .chain((0..20_000).map(|i| {
+            [
+                format!("group{}.item{i}", i % 50),
+                format!("Item {i} {{{{count}}}}"),
+                format!("条目 {i} {{{{count}}}}"),
+            ]
+        }))
+        .collect();
+    let rows: Vec<Vec<&str>> = rows
+        .iter()
+        .map(|row| row.iter().map(String::as_str).collect())
+        .collect();
+    let rows: Vec<&[&str]> = rows.iter().map(Vec::as_slice).collect();
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"], &["big", "nested"]]),
+            ("common", &[&["key", "en", "zh"], &["ok", "OK", "好"]]),
+            ("big", &rows),
+        ],
+    );
+
+    let normal = dir.path().join("normal");
+    let streamed = dir.path().join("streamed");
+    std::fs::create_dir_all(&normal).unwrap();
+    std::fs::create_dir_all(&streamed).unwrap();
+    export(&book, &normal, json!({})).unwrap();
+    export(&book, &streamed, json!({ "lowMemory": true })).unwrap();
+
+    for lang in ["en", "zh"] {
+        let file = format!("out/{lang}.json");
+        let expected = std::fs::read(normal.join(&file)).unwrap();
+        assert_eq!(expected, std::fs::read(streamed.join(&file)).unwrap());
+        let value: serde_json::Value = serde_json::from_slice(&expected).unwrap();
+        assert_eq!(value["big"]["group49"].as_object().unwrap().len(), 400);
+    }
+}
+
+/// en 列中有 #REF! 和 #DIV/0! 公式结果的工作表
+fn error_cell_workbook(path: &std::path::Path) {
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let config = workbook.add_worksheet();
+    config.set_name("导出语言管理").unwrap();
+    config.write_string(0, 0, "en").unwrap();
+    let sheets = workbook.add_worksheet();
+    sheets.set_name("导出sheet管理").unwrap();
+    sheets.write_string(0, 0, "common").unwrap();
+    sheets.write_string(0, 1, "root").unwrap();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("common").unwrap();
+    sheet.write_row(0, 0, ["key", "en"]).unwrap();
+    sheet.write_row(1, 0, ["ok", "OK"]).unwrap();
+    sheet.write_string(2, 0, "ref").unwrap();
+    sheet
+        .write_formula(
+            2,
+            1,
+            rust_xlsxwriter::Formula::new("=#REF!").set_result("#REF!"),
+        )
+        .unwrap();
+    sheet.write_string(3, 0, "ratio").unwrap();
+    sheet
+        .write_formula(
+            3,
+            1,
+            rust_xlsxwriter::Formula::new("=1/0").set_result("#DIV/0!"),
+        )
+        .unwrap();
+    workbook.save(path).unwrap();
+}
+
+#[test]
+fn error_cells_are_written_empty_with_a_warning() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("errors.xlsx");
+    error_cell_workbook(&book);
+
+    let report = export(&book, dir.path(), json!({})).unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK", "ref": "", "ratio": "" })
+    );
+    let cells: Vec<(Option<usize>, Option<&str>)> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::CellError)
+        .map(|i| (i.row, i.key.as_deref()))
+        .collect();
+    assert_eq!(cells, [(Some(3), Some("ref")), (Some(4), Some("ratio"))]);
+    assert!(report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::CellError)
+        .all(|i| i.level == IssueLevel::Warning && !i.message.contains("Error:")));
+}
+
+#[test]
+fn error_cells_abort_the_export_in_strict_mode() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("errors.xlsx");
+    error_cell_workbook(&book);
+
+    let err = export(&book, dir.path(), json!({ "strict": true })).unwrap_err();
+    assert!(
+        matches!(err, ExportError::CellError { row: 3, .. }),
+        "{err}"
+    );
+    assert!(err.to_string().contains("#REF!"), "{err}");
+}
+
+#[test]
+fn template_uses_output_encoding_and_requires_exported_base_language() {
+    let dir = tempfile::tempdir().unwrap();
+    export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({ "emitTemplate": true, "baseLanguage": "en", "writeBom": true, "indent": 0 }),
+    )
+    .unwrap();
+    let template = std::fs::read(dir.path().join("out/template.json")).unwrap();
+    let template = String::from_utf8(template).unwrap();
+    assert_eq!(
+        template
+            .strip_prefix('\u{FEFF}')
+            .map(|t| serde_json::from_str::<serde_json::Value>(t).unwrap()),
+        Some(json!({
+            "ok": "",
+            "hello": "",
+            "menu": { "file": { "open": "", "close": "" } },
+            "errors": { "e1": "" },
+            "partial": { "only": "" },
+        }))
+    );
+
+    let err = export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({
+            "emitTemplate": true,
+            "baseLanguage": "en",
+            "onlyLanguages": ["zh"],
+            "overwrite": true,
+        }),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("基准语言 en"), "{err}");
+}
+
+#[test]
+fn whitespace_only_values_count_as_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("blank.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en"],
+                    &["ok", "OK"],
+                    &["space", "  "],
+                    &["tab", "\t"],
+                ],
+            ),
+        ],
+    );
+    let empty_rows = |report: &excel2json_lib::ConvertReport| -> Vec<Option<usize>> {
+        report
+            .issues
+            .iter()
+            .filter(|i| i.category == IssueCategory::EmptyValue)
+            .map(|i| i.row)
+            .collect()
+    };
+
+    let report = export(&book, dir.path(), json!({})).unwrap();
+    assert_eq!(empty_rows(&report), [Some(3), Some(4)]);
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK", "space": "  ", "tab": "\t" })
+    );
+
+    export(
+        &book,
+        dir.path(),
+        json!({ "omitEmpty": true, "overwrite": true }),
+    )
+    .unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK" })
+    );
+
+    let report = export(
+        &book,
+        dir.path(),
+        json!({ "treatBlankAsEmpty": false, "overwrite": true }),
+    )
+    .unwrap();
+    assert!(empty_rows(&report).is_empty());
+}
+
+#[test]
+fn language_with_only_inline_comments_is_reported_empty() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("context.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "zh", "#context"],
+                    &["ok", "OK", "", "按钮文字"],
+                    &["cancel", "Cancel", "", "按钮文字"],
+                ],
+            ),
+        ],
+    );
+
+    let report = export(
+        &book,
+        dir.path(),
+        json!({ "contextColumn": "#context", "skipEmptyLanguages": true }),
+    )
+    .unwrap();
+    let empty: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::EmptyLanguage)
+        .map(|i| i.language.as_deref())
+        .collect();
+    assert_eq!(empty, [Some("zh")]);
+    assert!(!dir.path().join("out/zh.json").exists());
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json"))["ok_comment"],
+        "按钮文字"
+    );
+}
+
+#[test]
+fn key_whitespace_is_reported_once_per_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("keys.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "zh"],
+                    &["ok ", "OK", "好"],
+                    &[" cancel", "Cancel", "取消"],
+                ],
+            ),
+        ],
+    );
+
+    let report = export(&book, dir.path(), json!({ "trimKeys": true })).unwrap();
+    let whitespace: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::KeyWhitespace)
+        .map(|i| (i.row, i.key.as_deref(), i.language.as_deref()))
+        .collect();
+    assert_eq!(
+        whitespace,
+        [
+            (Some(2), Some("ok "), None),
+            (Some(3), Some(" cancel"), None)
+        ]
+    );
+    assert_eq!(
+        read_json(&dir.path().join("out/zh.json")),
+        json!({ "ok": "好", "cancel": "取消" })
+    );
+}
+
+#[test]
+fn invisible_key_chars_are_reported_once_per_key() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("keys.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[&["key", "en", "zh"], &["\u{200B}ok", "OK", "好"]],
+            ),
+        ],
+    );
+
+    let report = export(&book, dir.path(), json!({ "cleanInvisible": true })).unwrap();
+    let invisible: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::InvisibleChar)
+        .map(|i| (i.row, i.language.as_deref()))
+        .collect();
+    assert_eq!(invisible, [(Some(2), None)]);
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK" })
+    );
+}
+
+#[test]
+fn keys_colliding_across_root_sheets_are_all_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("collide.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"]]),
+            ("导出sheet管理", &[&["common", "root"], &["extra", "root"]]),
+            (
+                "common",
+                &[&["key", "en"], &["ok", "OK"], &["cancel", "Cancel"]],
+            ),
+            (
+                "extra",
+                &[&["key", "en"], &[" ok", "Okay"], &["cancel", "Cancel"]],
+            ),
+        ],
+    );
+
+    let report = export(&book, dir.path(), json!({ "trimKeys": true })).unwrap();
+    let collisions: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::KeyCollision)
+        .map(|i| (i.sheet.as_deref(), i.key.as_deref()))
+        .collect();
+    assert_eq!(
+        collisions,
+        [(Some("extra"), Some("ok")), (Some("extra"), Some("cancel"))]
+    );
+
+    let err = export(
+        &book,
+        dir.path(),
+        json!({ "trimKeys": true, "strict": true, "overwrite": true }),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ExportError::KeyCollisions { ref issues } if issues.len() == 2));
+}
+
+#[test]
+fn comments_file_uses_the_exported_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("context.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"]]),
+            ("导出sheet管理", &[&["common", "root"], &["menu", "nested"]]),
+            (
+                "common",
+                &[&["key", "en", "#context"], &["ok ", "OK", "按钮文字"]],
+            ),
+            (
+                "menu",
+                &[&["key", "en", "#context"], &["open", "Open", "菜单项"]],
+            ),
+        ],
+    );
+
+    export(
+        &book,
+        dir.path(),
+        json!({ "contextColumn": "#context", "commentStyle": "file", "trimKeys": true }),
+    )
+    .unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/comments.json")),
+        json!({ "ok": "按钮文字", "menu.open": "菜单项" })
+    );
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "ok": "OK", "menu": { "open": "Open" } })
+    );
+}
+
+#[test]
+fn cell_notes_follow_filled_merged_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("notes.xlsx");
+    let mut workbook = rust_xlsxwriter::Workbook::new();
+    let config = workbook.add_worksheet();
+    config.set_name("导出语言管理").unwrap();
+    config.write_string(0, 0, "en").unwrap();
+    let sheets = workbook.add_worksheet();
+    sheets.set_name("导出sheet管理").unwrap();
+    sheets.write_string(0, 0, "common").unwrap();
+    sheets.write_string(0, 1, "root").unwrap();
+    let sheet = workbook.add_worksheet();
+    sheet.set_name("common").unwrap();
+    sheet.write_row(0, 0, ["key", "en"]).unwrap();
+    sheet
+        .merge_range(1, 0, 2, 0, "title", &rust_xlsxwriter::Format::new())
+        .unwrap();
+    sheet.write_string(1, 1, "Title").unwrap();
+    sheet.write_string(2, 1, "Long title").unwrap();
+    sheet
+        .insert_note(
+            2,
+            1,
+            &rust_xlsxwriter::Note::new("最多 20 个字符").add_author_prefix(false),
+        )
+        .unwrap();
+    workbook.save(&book).unwrap();
+
+    export(
+        &book,
+        dir.path(),
+        json!({ "extractComments": true, "fillMergedKeys": true }),
+    )
+    .unwrap();
+    let notes = read_json(&dir.path().join("out/notes.json"));
+    assert_eq!(
+        notes["title"]["en"].as_str().map(str::trim_start),
+        Some("最多 20 个字符")
+    );
+}
+
+#[test]
+fn strings_match_written_files_without_creating_output() {
+    let dir = tempfile::tempdir().unwrap();
+    let strings = tempfile::tempdir().unwrap();
+    export(&fixture("basic.xlsx"), dir.path(), json!({})).unwrap();
+    let outputs =
+        convert_workbook_to_strings(&fixture("basic.xlsx"), options(strings.path(), json!({})))
+            .unwrap();
+
+    let languages: Vec<&str> = outputs.iter().map(|(lang, _)| lang.as_str()).collect();
+    assert_eq!(languages, ["en", "zh"]);
+    for (lang, content) in &outputs {
+        let written = fs::read_to_string(dir.path().join(format!("out/{lang}.json"))).unwrap();
+        assert_eq!(content, &written);
+    }
+    assert_eq!(fs::read_dir(strings.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn out_of_range_date_is_written_as_number_with_warning() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("dates.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"]]),
+            ("导出sheet管理", &[&["common", "root", "en:date"]]),
+            (
+                "common",
+                &[
+                    &["key", "en"],
+                    &["released", "=n:45000"],
+                    &["id", "=n:1000000000"],
+                ],
+            ),
+        ],
+    );
+    let report = export(&book, dir.path(), json!({})).unwrap();
+
+    assert_eq!(
+        read_json(&dir.path().join("out/en.json")),
+        json!({ "released": "2023-03-15", "id": "1000000000" })
+    );
+    let issues: Vec<_> = report
+        .issues
+        .iter()
+        .filter(|i| i.category == IssueCategory::DateOutOfRange)
+        .collect();
+    assert_eq!(issues.len(), 1);
+    assert_eq!(issues[0].level, IssueLevel::Warning);
+    assert_eq!(issues[0].row, Some(3));
+}
+
+#[test]
+fn only_languages_reads_fallback_ancestors_without_writing_them() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("fallback.xlsx");
+    write_workbook(
+        &book,
+        &[
+            (
+                "导出语言管理",
+                &[&["en"], &["en-GB", "", "en"], &["en-AU", "", "en-GB"]],
+            ),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "en-GB", "en-AU"],
+                    &["color", "Color", "Colour", ""],
+                    &["ok", "OK", "", ""],
+                ],
+            ),
+        ],
+    );
+    let report = export(&book, dir.path(), json!({ "onlyLanguages": ["en-AU"] })).unwrap();
+
+    assert_eq!(
+        read_json(&dir.path().join("out/en-AU.json")),
+        json!({ "color": "Colour", "ok": "OK" })
+    );
+    assert_eq!(report.language_count, 1);
+    assert!(!dir.path().join("out/en.json").exists());
+    assert!(!dir.path().join("out/en-GB.json").exists());
+    assert!(report
+        .issues
+        .iter()
+        .all(|i| i.language.as_deref().is_none_or(|l| l == "en-AU")));
+}
+
+#[test]
+fn json_to_excel_round_trips_through_export() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"], &["menu", "nested"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "zh"],
+                    &["hello", "Hi {{name}}", "你好 {{name}}"],
+                ],
+            ),
+            (
+                "menu",
+                &[
+                    &["key", "en", "zh"],
+                    &["file.open", "Open", "打开"],
+                    &["file.close", "Close", "关闭"],
+                ],
+            ),
+        ],
+    );
+    let first = dir.path().join("first");
+    fs::create_dir(&first).unwrap();
+    export(&book, &first, json!({})).unwrap();
+
+    // 转回的 xlsx 只有一个 key 列 + 语言列的工作表，用配置文件导出
+    let xlsx = dir.path().join("back.xlsx");
+    convert_json_dir_to_excel(&Progress::silent(), &first.join("out"), &xlsx).unwrap();
+    let config = dir.path().join("config.json");
+    fs::write(
+        &config,
+        json!({
+            "languages": [{ "code": "en" }, { "code": "zh" }],
+            "sheets": [{ "name": "Sheet1", "type": "root" }],
+        })
+        .to_string(),
+    )
+    .unwrap();
+    let second = dir.path().join("second");
+    fs::create_dir(&second).unwrap();
+    export(
+        &xlsx,
+        &second,
+        json!({ "configFile": config, "outputNesting": "nested" }),
+    )
+    .unwrap();
+
+    for lang in ["en", "zh"] {
+        let file = format!("out/{lang}.json");
+        assert_eq!(
+            read_json(&second.join(&file)),
+            read_json(&first.join(&file))
+        );
+    }
+}
+
+#[test]
+fn languages_are_inferred_from_headers_without_a_language_sheet() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("infer.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出sheet管理", &[&["common", "root"], &["menu", "nested"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "#notes", "", "status", "说明", "zh"],
+                    &["ok", "OK", "按钮", "x", "approved", "确认按钮", "好"],
+                ],
+            ),
+            ("menu", &[&["key", "fr", "en"], &["open", "Ouvrir", "Open"]]),
+        ],
+    );
+    let report = export(
+        &book,
+        dir.path(),
+        json!({
+            "statusColumn": "status",
+            "includeStatuses": ["approved"],
+            "contextColumn": "说明"
+        }),
+    )
+    .unwrap();
+
+    assert_eq!(report.language_count, 3);
+    let mut files: Vec<String> = fs::read_dir(dir.path().join("out"))
+        .unwrap()
+        .map(|e| e.unwrap().file_name().to_string_lossy().to_string())
+        .filter(|n| n != "report.json")
+        .collect();
+    files.sort();
+    assert_eq!(files, ["en.json", "fr.json", "zh.json"]);
+    assert_eq!(
+        read_json(&dir.path().join("out/zh.json")),
+        json!({ "ok": "好", "ok_comment": "确认按钮" })
+    );
+    assert_eq!(
+        read_json(&dir.path().join("out/fr.json"))["menu"],
+        json!({ "open": "Ouvrir" })
+    );
+}
+
+#[test]
+fn config_file_replaces_config_sheets_and_rejects_unknown_fields() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    write_workbook(
+        &book,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            (
+                "common",
+                &[
+                    &["通用文本", "", ""],
+                    &["key", "en", "en-GB"],
+                    &["color", "Color", "Colour"],
+                    &["ok", "OK", ""],
+                ],
+            ),
+        ],
+    );
+    let config = dir.path().join("excel2json.toml");
+    fs::write(
+        &config,
+        r#"
+[[languages]]
+code = "en"
+
+[[languages]]
+code = "en-GB"
+fallback = "en"
+
+[[sheets]]
+name = "common"
+type = "root"
+header_row = 2
+"#,
+    )
+    .unwrap();
+    export(&book, dir.path(), json!({ "configFile": config })).unwrap();
+    assert_eq!(
+        read_json(&dir.path().join("out/en-GB.json")),
+        json!({ "color": "Colour", "ok": "OK" })
+    );
+    assert!(!dir.path().join("out/zh.json").exists());
+
+    let typo = dir.path().join("typo.json");
+    fs::write(
+        &typo,
+        json!({ "languages": [{ "code": "en", "fallbak": "zh" }] }).to_string(),
+    )
+    .unwrap();
+    let err = export(&book, dir.path(), json!({ "configFile": typo })).unwrap_err();
+    assert!(err.to_string().contains("fallbak"), "{err}");
+
+    let yaml = dir.path().join("config.yaml");
+    fs::write(&yaml, "languages: []").unwrap();
+    let err = export(&book, dir.path(), json!({ "configFile": yaml })).unwrap_err();
+    assert!(err.to_string().contains("不支持的配置文件格式"), "{err}");
+}
+
+#[test]
+fn low_memory_fallback_uses_parent_values() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("fallback.xlsx");
+    write_workbook(
+        &book,
+        &[
+            (
+                "导出语言管理",
+                &[
+                    &["en"],
+                    &["en-GB", "", "en"],
+                    &["en-AU", "", "en-GB"],
+                    &["zh"],
+                ],
+            ),
+            ("导出sheet管理", &[&["common", "root"], &["menu", "nested"]]),
+            (
+                "common",
+                &[
+                    &["key", "en", "en-GB", "en-AU", "zh"],
+                    &["color", "Color", "Colour", "", "颜色"],
+                    &["ok", "OK", "", "", ""],
+                ],
+            ),
+            (
+                "menu",
+                &[
+                    &["key", "en", "en-GB", "en-AU", "zh"],
+                    &["open", "Open", "", "G'day", ""],
+                ],
+            ),
+        ],
+    );
+
+    let normal = dir.path().join("normal");
+    let streamed = dir.path().join("streamed");
+    fs::create_dir_all(&normal).unwrap();
+    fs::create_dir_all(&streamed).unwrap();
+    let settings = json!({ "baseLanguage": "en", "fallbackToBase": true });
+    export(&book, &normal, settings.clone()).unwrap();
+    let mut settings = settings;
+    settings["lowMemory"] = json!(true);
+    export(&book, &streamed, settings).unwrap();
+
+    assert_eq!(
+        read_json(&streamed.join("out/en-AU.json")),
+        json!({ "color": "Colour", "ok": "OK", "menu": { "open": "G'day" } })
+    );
+    assert_eq!(
+        read_json(&streamed.join("out/zh.json")),
+        json!({ "color": "颜色", "ok": "OK", "menu": { "open": "Open" } })
+    );
+    for lang in ["en", "en-GB", "en-AU", "zh"] {
+        let file = format!("out/{lang}.json");
+        assert_eq!(
+            read_json(&normal.join(&file)),
+            read_json(&streamed.join(&file))
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! 两次导出之间的对比 (diff_export_dirs) 和基于上次导出的增量导出 (since_dir)
+
+mod common;
+
+use std::fs;
+use std::path::Path;
+
+use common::{export, read_json, write_workbook};
+use excel2json_lib::{diff_export_dirs, Progress};
+use serde_json::json;
+
+/// 一个 root 工作表，各行为 (key, en, zh)
+fn workbook(path: &Path, rows: &[[&str; 3]]) {
+    let mut sheet: Vec<&[&str]> = vec![&["key", "en", "zh"]];
+    sheet.extend(rows.iter().map(|r| r.as_slice()));
+    write_workbook(
+        path,
+        &[
+            ("导出语言管理", &[&["en"], &["zh"]]),
+            ("导出sheet管理", &[&["common", "root"]]),
+            ("common", &sheet),
+        ],
+    );
+}
+
+/// 先后导出到 `old/out` 和 `new/out`: ok 的英文有修改，bye 被删除，hi 是新增的
+fn export_twice(
+    dir: &Path,
+    options: serde_json::Value,
+) -> (std::path::PathBuf, std::path::PathBuf) {
+    let book = dir.join("book.xlsx");
+    let (old, new) = (dir.join("old"), dir.join("new"));
+    fs::create_dir_all(&old).unwrap();
+    fs::create_dir_all(&new).unwrap();
+    workbook(&book, &[["ok", "OK", "好"], ["bye", "Bye", "再见"]]);
+    export(&book, &old, options.clone()).unwrap();
+    workbook(&book, &[["ok", "Okay", "好"], ["hi", "Hi", "你好"]]);
+    export(&book, &new, options).unwrap();
+    (old.join("out"), new.join("out"))
+}
+
+#[test]
+fn diff_reports_added_removed_and_changed_keys() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, new) = export_twice(dir.path(), json!({}));
+
+    let diff = diff_export_dirs(&Progress::silent(), &old, &new, None).unwrap();
+
+    let en = &diff.languages[0];
+    assert_eq!(en.language, "en");
+    assert_eq!(en.added.get("hi").map(String::as_str), Some("Hi"));
+    assert_eq!(en.removed.get("bye").map(String::as_str), Some("Bye"));
+    let change = &en.changed["ok"];
+    assert_eq!((change.old.as_str(), change.new.as_str()), ("OK", "Okay"));
+    let zh = &diff.languages[1];
+    assert_eq!(
+        (zh.added.len(), zh.removed.len(), zh.changed.len()),
+        (1, 1, 0)
+    );
+    assert_eq!(
+        (diff.added_count, diff.removed_count, diff.changed_count),
+        (2, 2, 1)
+    );
+    assert!(!new.join("diff.json").exists());
+}
+
+#[test]
+fn diff_reads_exports_with_a_filename_pattern() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, new) = export_twice(dir.path(), json!({ "layout": "i18next" }));
+
+    let err = diff_export_dirs(&Progress::silent(), &old, &new, None).unwrap_err();
+    assert!(err.to_string().contains("没有符合文件名模式"), "{err}");
+
+    let pattern = Some("locales/{lang}/translation.{ext}");
+    let diff = diff_export_dirs(&Progress::silent(), &old, &new, pattern).unwrap();
+    let languages: Vec<&str> = diff.languages.iter().map(|l| l.language.as_str()).collect();
+    assert_eq!(languages, ["en", "zh"]);
+    assert_eq!(diff.changed_count, 1);
+}
+
+#[test]
+fn since_dir_follows_the_filename_pattern() {
+    let dir = tempfile::tempdir().unwrap();
+    let book = dir.path().join("book.xlsx");
+    let options = json!({ "filenamePattern": "locale.{lang}.json", "overwrite": true });
+    workbook(&book, &[["ok", "OK", "好"], ["bye", "Bye", "再见"]]);
+    let previous = dir.path().join("previous");
+    fs::create_dir(&previous).unwrap();
+    export(&book, &previous, options.clone()).unwrap();
+
+    workbook(&book, &[["ok", "Okay", "好"], ["bye", "Bye", "再见"]]);
+    let mut options = options;
+    options["sinceDir"] = json!(previous.join("out"));
+    export(&book, dir.path(), options).unwrap();
+
+    assert_eq!(
+        read_json(&dir.path().join("out/locale.en.json")),
+        json!({ "ok": "Okay" })
+    );
+    assert_eq!(read_json(&dir.path().join("out/locale.zh.json")), json!({}));
+}
+
+#[test]
+fn since_dir_rejects_split_exports() {
+    let dir = tempfile::tempdir().unwrap();
+    let (old, _) = export_twice(dir.path(), json!({ "splitBySheet": true }));
+    let book = dir.path().join("book.xlsx");
+
+    let err = export(
+        &book,
+        dir.path(),
+        json!({ "splitBySheet": true, "sinceDir": old }),
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("按工作表拆分"), "{err}");
+}
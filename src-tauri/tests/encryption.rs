@@ -0,0 +1,46 @@
+//! 加密工作簿: 固定的测试文件是 basic.xlsx 按 MS-OFFCRYPTO 加密后的结果，密码为 `secret`，
+//! 由脚本生成而不是 Excel 保存的 (agile 为 SHA-512 / AES-256，标准加密为 AES-128)
+
+mod common;
+
+use common::{export, fixture, read_json};
+use excel2json_lib::{EncryptionProblem, ExportError};
+use serde_json::json;
+
+fn problem(err: ExportError) -> EncryptionProblem {
+    match err {
+        ExportError::Encryption { problem, .. } => problem,
+        other => panic!("不是加密错误: {other}"),
+    }
+}
+
+#[test]
+fn encrypted_workbooks_open_with_the_password() {
+    for name in ["encrypted_agile.xlsx", "encrypted_standard.xlsx"] {
+        let dir = tempfile::tempdir().unwrap();
+        export(&fixture(name), dir.path(), json!({ "password": "secret" })).unwrap();
+        assert_eq!(
+            read_json(&dir.path().join("out/en.json"))["ok"],
+            "Okay",
+            "{name}"
+        );
+    }
+}
+
+#[test]
+fn encrypted_workbook_problems_are_reported() {
+    let dir = tempfile::tempdir().unwrap();
+    for name in ["encrypted_agile.xlsx", "encrypted_standard.xlsx"] {
+        let err = export(&fixture(name), dir.path(), json!({})).unwrap_err();
+        assert_eq!(problem(err), EncryptionProblem::PasswordRequired, "{name}");
+        let err = export(&fixture(name), dir.path(), json!({ "password": "wrong" })).unwrap_err();
+        assert_eq!(problem(err), EncryptionProblem::WrongPassword, "{name}");
+    }
+    let err = export(
+        &fixture("basic.xlsx"),
+        dir.path(),
+        json!({ "password": "secret" }),
+    )
+    .unwrap_err();
+    assert_eq!(problem(err), EncryptionProblem::NotEncrypted);
+}
@@ -18,19 +18,61 @@ function App() {
     setLogs([]);
   };
 
-  async function handleConvert() {
-    const file = await open({
-      filters: [{ name: "Excel 文件", extensions: ["xlsx"] }],
+  async function handleCancel() {
+    await invoke("cancel_export");
+  }
+
+  function pickExcel(multiple = false) {
+    return open({
+      multiple,
+      filters: [
+        {
+          name: "Excel 文件",
+          extensions: ["xlsx", "xlsm", "xlsb", "xls", "ods"],
+        },
+      ],
     });
+  }
+
+  async function handleConvert() {
+    const file = await pickExcel();
     if (!file) return;
 
     try {
       await invoke("convert_excel_to_json", { path: file });
     } catch (err) {
-      appendLog("转换失败：" + err, "error");
+      appendLog("转换失败：" + (err?.message ?? err), "error");
     }
   }
 
+  // 一次选择多个工作簿，逐个导出
+  async function handleBatch() {
+    const files = await pickExcel(true);
+    if (!files || files.length === 0) return;
+
+    try {
+      await invoke("convert_excel_batch", { paths: files });
+    } catch (err) {
+      appendLog("批量转换失败：" + (err?.message ?? err), "error");
+    }
+  }
+
+  // 监听文件，保存后自动重新导出
+  async function handleWatch() {
+    const file = await pickExcel();
+    if (!file) return;
+
+    try {
+      await invoke("watch_excel", { path: file });
+    } catch (err) {
+      appendLog("监听失败：" + (err?.message ?? err), "error");
+    }
+  }
+
+  async function handleStopWatch() {
+    await invoke("stop_watch");
+  }
+
   // 自动滚动到底部
   useEffect(() => {
     if (logRef.current) {
@@ -73,6 +115,10 @@ function App() {
       <h1>📘 Excel 多语言导出工具</h1>
       <div className="row space-x-[20px]">
         <button onClick={handleConvert}>选择Excel并开始导出 JSON</button>
+        <button onClick={handleBatch}>批量导出</button>
+        <button onClick={handleCancel}>取消</button>
+        <button onClick={handleWatch}>监听文件</button>
+        <button onClick={handleStopWatch}>停止监听</button>
         <button onClick={handleClear}>清空</button>
       </div>
       <div